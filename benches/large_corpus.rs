@@ -0,0 +1,35 @@
+//! Times sorting of a million generated file names, run with `cargo bench`.
+//!
+//! Comparing runs with and without `--features simd` shows the effect of the SIMD scanning.
+extern crate humanesort;
+
+use std::time::{Duration, Instant};
+use humanesort::{HumaneSortable, HumaneSortableByKey};
+
+/// Names with long shared prefixes and numbers, like the files of a photo library.
+fn corpus(len: u64) -> Vec<String> {
+    (0..len).map(|i| {
+        let n = i.wrapping_mul(2_654_435_761) % 1_000_003;
+        match i % 3 {
+            0 => format!("holiday-photos-2023-08-14/IMG_{:08}.jpeg", n),
+            1 => format!("scans/archive-volume-{}/page-{}.png", n % 40, n),
+            _ => format!("project-reports-final-revision-{}-of-{}.pdf", n % 25, n)
+        }
+    }).collect()
+}
+
+/// Returns the fastest of a few runs of `sort` on copies of `names`.
+fn best_of<F>(names: &[String], mut sort: F) -> Duration where F: FnMut(&mut Vec<String>) {
+    (0..3).map(|_| {
+        let mut names = names.to_vec();
+        let start = Instant::now();
+        sort(&mut names);
+        start.elapsed()
+    }).min().unwrap()
+}
+
+fn main() {
+    let names = corpus(1_000_000);
+    println!("humane_sort:               {:?}", best_of(&names, |names| names.humane_sort()));
+    println!("humane_sort_by_cached_key: {:?}", best_of(&names, |names| names.humane_sort_by_cached_key(|name| name.clone())));
+}
@@ -0,0 +1,111 @@
+//! `#[derive(HumaneOrder)]` for structs, use it through the `derive` feature of `humanesort`.
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Field, Index, Member};
+
+/// Derives `HumaneOrder` by comparing the fields of a struct in declaration order.
+///
+/// Fields marked `#[humane(skip)]` are left out. When any field is marked `#[humane(key)]`,
+/// only the key fields are compared.
+#[proc_macro_derive(HumaneOrder, attributes(humane))]
+pub fn derive_humane_order(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into()
+    }
+}
+
+/// How a field takes part in the comparison.
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Role {
+    Compared,
+    Key,
+    Skipped
+}
+
+fn role(field: &Field) -> syn::Result<Role> {
+    let mut role = Role::Compared;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("humane")) {
+        attr.parse_nested_meta(|meta| {
+            let parsed = match meta.path.get_ident().map(|ident| ident.to_string()) {
+                Some(ref name) if name == "key" => Role::Key,
+                Some(ref name) if name == "skip" => Role::Skipped,
+                _ => return Err(meta.error("expected `key` or `skip`"))
+            };
+            match role == Role::Compared || role == parsed {
+                true => role = parsed,
+                false => return Err(meta.error("a field cannot be both `key` and `skip`"))
+            }
+            Ok(())
+        })?;
+    }
+    Ok(role)
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => return Err(Error::new_spanned(&input.ident, "HumaneOrder can only be derived for structs"))
+    };
+    let mut roles = Vec::new();
+    for field in fields {
+        roles.push(role(field)?);
+    }
+    let wanted = match roles.contains(&Role::Key) {
+        true => Role::Key,
+        false => Role::Compared
+    };
+    let compared: Vec<(Member, &Field)> = fields.iter().enumerate()
+        .filter(|&(i, _)| roles[i] == wanted)
+        .map(|(i, field)| match field.ident {
+            Some(ref ident) => (Member::Named(ident.clone()), field),
+            None => (Member::Unnamed(Index::from(i)), field)
+        })
+        .collect();
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| syn::parse_quote!(where));
+    for &(_, field) in &compared {
+        let ty = &field.ty;
+        where_clause.predicates.push(syn::parse_quote!(#ty: ::humanesort::HumaneOrder));
+    }
+    let members: Vec<&Member> = compared.iter().map(|(member, _)| member).collect();
+    Ok(quote! {
+        impl #impl_generics ::humanesort::HumaneOrder for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn humane_cmp(&self, other: &Self) -> ::humanesort::__private::Ordering {
+                ::humanesort::__private::Ordering::Equal
+                    #(.then_with(|| ::humanesort::HumaneOrder::humane_cmp(&self.#members, &other.#members)))*
+            }
+
+            #[allow(unused_variables)]
+            fn humane_cmp_with(&self, other: &Self, options: &::humanesort::SortOptions) -> ::humanesort::__private::Ordering {
+                ::humanesort::__private::Ordering::Equal
+                    #(.then_with(|| ::humanesort::HumaneOrder::humane_cmp_with(&self.#members, &other.#members, options)))*
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+
+    #[test]
+    fn rejects_invalid_input() {
+        let error = |input: &str| expand(&syn::parse_str(input).unwrap()).unwrap_err().to_string();
+        assert_eq!(error("enum E { A }"), "HumaneOrder can only be derived for structs");
+        assert_eq!(error("struct S { #[humane(key, skip)] a: String }"), "a field cannot be both `key` and `skip`");
+        assert_eq!(error("struct S { #[humane(first)] a: String }"), "expected `key` or `skip`");
+        let tuple = expand(&syn::parse_str("struct S<T>(#[humane(skip)] u8, T);").unwrap()).unwrap().to_string();
+        assert!(tuple.contains("T : :: humanesort :: HumaneOrder"));
+        assert!(tuple.contains("self . 1") && !tuple.contains("self . 0"));
+    }
+}
@@ -0,0 +1,22 @@
+/* C declarations of the functions exported by humanesort with the `ffi` feature. */
+#ifndef HUMANESORT_H
+#define HUMANESORT_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Compares two NUL-terminated strings humanely, returning -1, 0 or 1 like strcmp.
+   A null pointer compares like the empty string. */
+int humane_strcmp(const char *a, const char *b);
+
+/* Compares two strings of the given lengths in bytes, which need no NUL terminator. */
+int humane_strcmp_len(const char *a, size_t a_len, const char *b, size_t b_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,236 @@
+//! The help text, the manual page and the shell completions, all generated from `OPTIONS`.
+use ::{Opt, Shell, OPTIONS};
+
+/// The ways to call the program, without its name.
+const SYNOPSIS: &[&str] = &["[OPTION]... [FILE]...", "comm [OPTION]... FILE1 FILE2", "completions SHELL", "man"];
+
+const DESCRIPTION: &[&str] = &[
+    "Write the lines of all FILEs in humane order to standard output.",
+    "With no FILE, or when FILE is -, read standard input."
+];
+
+const COMMANDS: &str = "The comm command compares two files sorted with the same options and writes the lines \
+    only in FILE1, the lines only in FILE2 indented by a tab and the lines in both files indented by two tabs. \
+    The completions command writes a completion script for SHELL, which is bash, zsh or \
+    fish. The man command writes the manual page.";
+
+const ENVIRONMENT: &str = "Defaults for the options are read from the TOML file $HUMANESORT_CONFIG, or else \
+    humanesort/config.toml in $XDG_CONFIG_HOME or ~/.config, whose keys are the long option names, such as \
+    ignore-case = true or preset = \"gnu\". Options in HUMANESORT_OPTS override the file and the command line \
+    overrides both.";
+
+/// The width of the help text and the column option descriptions start at.
+const WIDTH: usize = 80;
+const HELP_COLUMN: usize = 34;
+
+/// Splits `text` into lines of at most `width` characters, breaking at spaces.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string())
+        }
+    }
+    lines
+}
+
+/// Returns the long form of an option with the name of its value, such as "--parallel=N".
+fn long_name(option: &Opt) -> String {
+    match option.value {
+        Some(value) => format!("--{}={}", option.long, value),
+        None => format!("--{}", option.long)
+    }
+}
+
+/// Returns the text of `--help`.
+pub fn usage() -> String {
+    let mut usage = String::new();
+    for (i, synopsis) in SYNOPSIS.iter().enumerate() {
+        let prefix = match i {
+            0 => "Usage:",
+            _ => "  or: "
+        };
+        usage += &format!("{} humanesort {}\n", prefix, synopsis);
+    }
+    for sentence in DESCRIPTION {
+        usage += &format!("{}\n", sentence);
+    }
+    usage.push('\n');
+    for option in OPTIONS {
+        let short = option.short.map_or_else(|| "    ".to_string(), |short| format!("-{}, ", short));
+        let name = format!("  {}{}", short, long_name(option));
+        for (i, line) in wrap(option.help, WIDTH - HELP_COLUMN).iter().enumerate() {
+            let lead = match i {
+                0 => name.as_str(),
+                _ => ""
+            };
+            usage += &format!("{:<2$}{}\n", lead, line, HELP_COLUMN);
+        }
+    }
+    for text in &[COMMANDS, ENVIRONMENT] {
+        usage.push('\n');
+        for line in wrap(text, WIDTH) {
+            usage += &format!("{}\n", line);
+        }
+    }
+    usage
+}
+
+/// Escapes text for roff.
+fn roff(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('-', "\\-")
+}
+
+/// Returns the manual page in roff format.
+pub fn man_page() -> String {
+    let mut page = format!(".TH HUMANESORT 1 \"\" \"humanesort {}\" \"User Commands\"\n", env!("CARGO_PKG_VERSION"));
+    page += ".SH NAME\nhumanesort \\- write lines in humane order\n.SH SYNOPSIS\n";
+    for (i, synopsis) in SYNOPSIS.iter().enumerate() {
+        if i > 0 {
+            page += ".br\n";
+        }
+        page += &format!(".B humanesort\n{}\n", roff(synopsis));
+    }
+    page += &format!(".SH DESCRIPTION\n{}\n.SH OPTIONS\n", roff(&DESCRIPTION.join(" ")));
+    for option in OPTIONS {
+        let short = option.short.map_or_else(String::new, |short| format!("\\fB\\-{}\\fR, ", short));
+        let value = option.value.map_or_else(String::new, |value| format!("=\\fI{}\\fR", value));
+        page += &format!(".TP\n{}\\fB\\-\\-{}\\fR{}\n{}\n", short, roff(option.long), value, roff(option.help));
+    }
+    page += &format!(".SH COMMANDS\n{}\n.SH ENVIRONMENT\n{}\n", roff(COMMANDS), roff(ENVIRONMENT));
+    page
+}
+
+/// Returns the completion script for `shell`.
+pub fn completions(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => bash(),
+        Shell::Zsh => zsh(),
+        Shell::Fish => fish()
+    }
+}
+
+/// Returns the names an option can be given by, such as "-S" and "--buffer-size".
+fn names(option: &Opt) -> Vec<String> {
+    option.short.map(|short| format!("-{}", short)).into_iter().chain(Some(format!("--{}", option.long))).collect()
+}
+
+const BASH: &str = r#"_humanesort() {
+    local cur=${COMP_WORDS[COMP_CWORD]} prev=${COMP_WORDS[COMP_CWORD-1]}
+    if [[ $COMP_CWORD -eq 2 && ${COMP_WORDS[1]} == completions ]]; then
+        COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur"))
+        return
+    fi
+    case $prev in
+@VALUES@    esac
+    case $cur in
+        -*) COMPREPLY=($(compgen -W "@NAMES@" -- "$cur")) ;;
+        *) COMPREPLY=($(compgen -f -- "$cur"))
+           if [[ $COMP_CWORD -eq 1 ]]; then
+               COMPREPLY+=($(compgen -W "comm completions man" -- "$cur"))
+           fi ;;
+    esac
+}
+complete -o filenames -F _humanesort humanesort
+"#;
+
+fn bash() -> String {
+    let mut values = String::new();
+    for option in OPTIONS.iter().filter(|option| option.value.is_some()) {
+        let complete = match option.value {
+            Some("DIR") => "COMPREPLY=($(compgen -d -- \"$cur\")); ",
+            Some("FILE") => "COMPREPLY=($(compgen -f -- \"$cur\")); ",
+            _ => ""
+        };
+        values += &format!("        {}) {}return ;;\n", names(option).join("|"), complete);
+    }
+    let names: Vec<String> = OPTIONS.iter().flat_map(names).collect();
+    BASH.replace("@VALUES@", &values).replace("@NAMES@", &names.join(" "))
+}
+
+const ZSH: &str = r#"#compdef humanesort
+
+_humanesort() {
+    if [[ $words[2] == completions ]]; then
+        _arguments '2:shell:(bash zsh fish)'
+        return
+    fi
+    _arguments -s -S \
+@SPECS@        '1: :{_alternative "commands:command:(comm completions man)" "files:file:_files"}' \
+        '*:file:_files'
+}
+
+_humanesort "$@"
+"#;
+
+fn zsh() -> String {
+    let mut specs = String::new();
+    for option in OPTIONS {
+        let help = option.help.replace('\'', "'\\''").replace('[', "\\[").replace(']', "\\]");
+        let value = match option.value {
+            Some(value) => format!(":{}:{}", value, match value {
+                "DIR" => "_directories",
+                "FILE" => "_files",
+                _ => ""
+            }),
+            None => String::new()
+        };
+        let (short_suffix, long_suffix) = match option.value {
+            Some(_) => ("+", "="),
+            None => ("", "")
+        };
+        let names = match option.short {
+            Some(short) => format!("'(-{0} --{1})'{{-{0}{2},--{1}{3}}}'", short, option.long, short_suffix, long_suffix),
+            None => format!("'--{}{}", option.long, long_suffix)
+        };
+        specs += &format!("        {}[{}]{}' \\\n", names, help, value);
+    }
+    ZSH.replace("@SPECS@", &specs)
+}
+
+fn fish() -> String {
+    let mut script = String::from("complete -c humanesort -n __fish_use_subcommand -a 'comm completions man'\n");
+    script += "complete -c humanesort -n '__fish_seen_subcommand_from completions' -f -a 'bash zsh fish'\n";
+    for option in OPTIONS {
+        let short = option.short.map_or_else(String::new, |short| format!(" -s {}", short));
+        let value = match option.value {
+            Some("DIR") => " -x -a '(__fish_complete_directories)'",
+            Some("FILE") => " -r -F",
+            Some(_) => " -x",
+            None => ""
+        };
+        let help = option.help.replace('\\', "\\\\").replace('\'', "\\'");
+        script += &format!("complete -c humanesort{} -l {}{} -d '{}'\n", short, option.long, value, help);
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{completions, man_page, usage, wrap};
+    use ::{Shell, OPTIONS};
+
+    #[test]
+    fn lists_all_options() {
+        let usage = usage();
+        assert!(usage.starts_with("Usage: humanesort [OPTION]... [FILE]...\n  or:  humanesort comm [OPTION]... FILE1 FILE2\n"));
+        assert!(usage.contains("  -S, --buffer-size=SIZE          keep at most SIZE of lines in memory and sort\n"));
+        assert!(usage.lines().all(|line| line.chars().count() <= 80));
+        let man = man_page();
+        for option in OPTIONS {
+            assert!(usage.contains(&format!("--{}", option.long)), "{}", option.long);
+            assert!(man.contains(&format!("\\fB\\-\\-{}\\fR", option.long.replace('-', "\\-"))), "{}", option.long);
+            for &shell in &[Shell::Bash, Shell::Zsh, Shell::Fish] {
+                assert!(completions(shell).contains(option.long), "{:?} {}", shell, option.long);
+            }
+        }
+        assert!(completions(Shell::Bash).contains("        -T|--temporary-directory) COMPREPLY=($(compgen -d -- \"$cur\")); return ;;\n"));
+        assert!(completions(Shell::Zsh).contains("        '(-f --ignore-case)'{-f,--ignore-case}'[compare letters alphabetically, ignoring case]' \\\n"));
+        assert!(completions(Shell::Fish).contains("complete -c humanesort -l parallel -x -d 'sort with N threads instead of one'\n"));
+        assert_eq!(wrap("a bb ccc", 4), vec!["a bb", "ccc"]);
+    }
+}
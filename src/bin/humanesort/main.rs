@@ -0,0 +1,761 @@
+//! Writes the lines of files or standard input in humane order.
+extern crate humanesort;
+extern crate toml_edit;
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::env;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use humanesort::{filevercmp, CaseOrder, HumaneOrder, SortOptions};
+#[cfg(feature = "icu")]
+use humanesort::Collation;
+use humanesort::iter::{merge_join, EitherOrBoth};
+use humanesort::parallel::sort_by_parallel;
+use toml_edit::{DocumentMut, Value};
+
+mod help;
+
+/// An option of the command line, the parser, the help text, the manual page and the shell
+/// completions are all built from `OPTIONS`.
+struct Opt {
+    short: Option<char>,
+    long: &'static str,
+    /// The name of the value the option takes, if it takes one.
+    value: Option<&'static str>,
+    help: &'static str
+}
+
+const OPTIONS: &[Opt] = &[
+    Opt { short: Some('f'), long: "ignore-case", value: None, help: "compare letters alphabetically, ignoring case" },
+    Opt { short: None, long: "gnu", value: None, help: "order exactly like GNU sort -V" },
+    Opt { short: None, long: "preset", value: Some("NAME"), help: "order lines the NAME way, humane (the default) or gnu like --gnu" },
+    Opt {
+        short: None, long: "locale", value: Some("LOCALE"),
+        help: "compare letters in the alphabetical order of LOCALE, which defaults to LC_ALL, LC_COLLATE or LANG; needs the icu feature"
+    },
+    Opt { short: Some('r'), long: "reverse", value: None, help: "reverse the result of comparisons" },
+    Opt { short: Some('u'), long: "unique", value: None, help: "output only the first of lines that compare equal" },
+    Opt {
+        short: Some('S'), long: "buffer-size", value: Some("SIZE"),
+        help: "keep at most SIZE of lines in memory and sort larger inputs in runs on disk, SIZE is in kibibytes unless followed by b, K, M, G or T"
+    },
+    Opt { short: Some('T'), long: "temporary-directory", value: Some("DIR"), help: "write the runs to DIR instead of the system default" },
+    Opt { short: None, long: "parallel", value: Some("N"), help: "sort with N threads instead of one" },
+    Opt {
+        short: None, long: "files0-from", value: Some("FILE"),
+        help: "read the input from the files named in FILE, ended by NUL characters like the output of find -print0; - reads the names from standard input"
+    },
+    Opt { short: Some('1'), long: "hide-first", value: None, help: "with comm, hide the lines only in FILE1" },
+    Opt { short: Some('2'), long: "hide-second", value: None, help: "with comm, hide the lines only in FILE2" },
+    Opt { short: Some('3'), long: "hide-common", value: None, help: "with comm, hide the lines in both files" },
+    Opt { short: Some('h'), long: "help", value: None, help: "display this help and exit" }
+];
+
+/// A shell to write completions for.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish
+}
+
+/// A command that is run instead of sorting.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Command {
+    Completions(Shell),
+    Man,
+    /// Compares two sorted files.
+    Comm
+}
+
+/// The parsed command line.
+#[derive(PartialEq, Eq, Debug, Default)]
+struct Args {
+    command: Option<Command>,
+    ignore_case: bool,
+    gnu: bool,
+    reverse: bool,
+    unique: bool,
+    help: bool,
+    buffer_size: Option<usize>,
+    temporary_directory: Option<String>,
+    parallel: Option<usize>,
+    locale: Option<String>,
+    files0_from: Option<String>,
+    /// The `comm` columns to hide: lines only in the first file, only in the second and in both.
+    hide: [bool; 3],
+    files: Vec<String>
+}
+
+/// Parses the arguments following the program name on top of `parsed`, which holds the defaults.
+///
+/// Commands are only recognized as the first argument, "./man" or "-- man" sort a file of that
+/// name.
+fn parse_args<I>(mut parsed: Args, args: I) -> Result<Args, String> where I: IntoIterator<Item = String> {
+    let mut args = args.into_iter().peekable();
+    match args.peek().map(String::as_str) {
+        Some("completions") => {
+            let shell = match args.nth(1).as_deref() {
+                Some("bash") => Shell::Bash,
+                Some("zsh") => Shell::Zsh,
+                Some("fish") => Shell::Fish,
+                Some(shell) => return Err(format!("unsupported shell '{}'", shell)),
+                None => return Err("missing shell after 'completions'".to_string())
+            };
+            parsed.command = Some(Command::Completions(shell));
+        }
+        Some("man") => {
+            args.next();
+            parsed.command = Some(Command::Man);
+        }
+        Some("comm") => {
+            args.next();
+            parsed.command = Some(Command::Comm);
+        }
+        _ => {}
+    }
+    let operands = matches!(parsed.command, None | Some(Command::Comm));
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => {
+                parsed.files.extend(args);
+                break
+            }
+            _ if !operands => return Err(format!("extra operand '{}'", arg)),
+            _ if arg.starts_with("--") => {
+                let (name, value) = match arg.find('=') {
+                    Some(i) => (&arg[2..i], Some(arg[i + 1..].to_string())),
+                    None => (&arg[2..], None)
+                };
+                let option = OPTIONS.iter().find(|option| option.long == name)
+                    .ok_or_else(|| format!("unrecognized option '{}'", arg))?;
+                let value = match (option.value, value) {
+                    (Some(_), None) => Some(args.next().ok_or_else(|| format!("option '--{}' requires an argument", name))?),
+                    (None, Some(_)) => return Err(format!("option '--{}' doesn't allow an argument", name)),
+                    (_, value) => value
+                };
+                set_option(&mut parsed, option.long, value)?;
+            }
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                for (i, flag) in arg.char_indices().skip(1) {
+                    let option = OPTIONS.iter().find(|option| option.short == Some(flag))
+                        .ok_or_else(|| format!("invalid option -- '{}'", flag))?;
+                    if option.value.is_none() {
+                        set_option(&mut parsed, option.long, None)?;
+                        continue
+                    }
+                    // The rest of the argument is the value, or the next argument if there is
+                    // no rest.
+                    let value = match arg[i + 1..].is_empty() {
+                        true => args.next().ok_or_else(|| format!("option requires an argument -- '{}'", flag))?,
+                        false => arg[i + 1..].to_string()
+                    };
+                    set_option(&mut parsed, option.long, Some(value))?;
+                    break
+                }
+            }
+            _ => parsed.files.push(arg)
+        }
+    }
+    if parsed.command == Some(Command::Comm) && parsed.files.len() != 2 && !parsed.help {
+        return Err("comm compares exactly two files".to_string())
+    }
+    Ok(parsed)
+}
+
+/// Sets the option with the long name `name`, `value` is set for options that take one.
+fn set_option(args: &mut Args, name: &str, value: Option<String>) -> Result<(), String> {
+    let value = value.unwrap_or_default();
+    match name {
+        "ignore-case" => args.ignore_case = true,
+        "gnu" => args.gnu = true,
+        "reverse" => args.reverse = true,
+        "unique" => args.unique = true,
+        "buffer-size" => args.buffer_size = Some(parse_size(&value).ok_or_else(|| format!("invalid buffer size '{}'", value))?),
+        "temporary-directory" => args.temporary_directory = Some(value),
+        "parallel" => args.parallel = Some(value.parse().ok().filter(|&n| n > 0).ok_or_else(|| format!("invalid number of threads '{}'", value))?),
+        "preset" => args.gnu = match value.as_str() {
+            "humane" => false,
+            "gnu" => true,
+            _ => return Err(format!("unknown preset '{}'", value))
+        },
+        "locale" => args.locale = Some(check_locale(value)?),
+        "files0-from" => args.files0_from = Some(value),
+        "hide-first" => args.hide[0] = true,
+        "hide-second" => args.hide[1] = true,
+        "hide-common" => args.hide[2] = true,
+        _ => args.help = true
+    }
+    Ok(())
+}
+
+/// Returns `locale` if there is collation data for it.
+#[cfg(feature = "icu")]
+fn check_locale(locale: String) -> Result<String, String> {
+    match Collation::new(&locale) {
+        Ok(_) => Ok(locale),
+        Err(err) => Err(err.to_string())
+    }
+}
+
+#[cfg(not(feature = "icu"))]
+fn check_locale(locale: String) -> Result<String, String> {
+    Err(format!("locale '{}' needs humanesort built with the icu feature", locale))
+}
+
+/// Returns the collation locale of the environment, from the first of `LC_ALL`, `LC_COLLATE` and
+/// `LANG` that is set. Locales without collation data are ignored like the C locale.
+#[cfg(feature = "icu")]
+fn environment_locale() -> Option<String> {
+    let locale = ["LC_ALL", "LC_COLLATE", "LANG"].iter().filter_map(|name| env::var(name).ok()).find(|locale| !locale.is_empty())?;
+    posix_locale(&locale).and_then(|locale| check_locale(locale).ok())
+}
+
+#[cfg(not(feature = "icu"))]
+fn environment_locale() -> Option<String> {
+    None
+}
+
+/// Turns a POSIX locale name such as "de_AT.UTF-8@euro" into a BCP 47 identifier, the C and POSIX
+/// locales have none.
+#[cfg(feature = "icu")]
+fn posix_locale(locale: &str) -> Option<String> {
+    match locale.split(['.', '@']).next().unwrap_or_default() {
+        "" | "C" | "POSIX" => None,
+        name => Some(name.replace('_', "-"))
+    }
+}
+
+/// Returns the path of the config file and whether it was named by `HUMANESORT_CONFIG`.
+fn config_path() -> Option<(PathBuf, bool)> {
+    if let Some(path) = env::var_os("HUMANESORT_CONFIG") {
+        return Some((PathBuf::from(path), true))
+    }
+    let dir = env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()).map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some((dir.join("humanesort").join("config.toml"), false))
+}
+
+/// Sets the options of a config file, its keys are the long option names with booleans for
+/// flags and strings or integers for options that take a value.
+fn apply_config(args: &mut Args, text: &str) -> Result<(), String> {
+    let document = text.parse::<DocumentMut>().map_err(|err| err.to_string())?;
+    for (key, item) in document.iter() {
+        let option = OPTIONS.iter().find(|option| option.long == key && option.long != "help")
+            .ok_or_else(|| format!("unknown option '{}'", key))?;
+        let value = match (option.value, item.as_value()) {
+            (None, Some(Value::Boolean(flag))) => match *flag.value() {
+                true => None,
+                false => continue
+            },
+            (Some(_), Some(Value::String(value))) => Some(value.value().clone()),
+            (Some(_), Some(Value::Integer(value))) => Some(value.value().to_string()),
+            _ => return Err(format!("invalid value for '{}'", key))
+        };
+        set_option(args, option.long, value)?;
+    }
+    Ok(())
+}
+
+/// Sets the options in `opts`, the value of `HUMANESORT_OPTS`.
+fn apply_opts(args: Args, opts: &str) -> Result<Args, String> {
+    let args = parse_args(args, opts.split_whitespace().map(str::to_string))?;
+    match args.command.is_none() && args.files.is_empty() {
+        true => Ok(args),
+        false => Err("only options are allowed".to_string())
+    }
+}
+
+/// Returns the defaults the command line is parsed on top of: the locale of the environment,
+/// then the config file, then `HUMANESORT_OPTS`.
+fn defaults() -> Result<Args, String> {
+    let mut args = Args { locale: environment_locale(), ..Args::default() };
+    if let Some((path, named)) = config_path() {
+        match fs::read_to_string(&path) {
+            Ok(text) => apply_config(&mut args, &text).map_err(|message| format!("{}: {}", path.display(), message))?,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound && !named => {}
+            Err(err) => return Err(format!("{}: {}", path.display(), err))
+        }
+    }
+    match env::var("HUMANESORT_OPTS") {
+        Ok(opts) => apply_opts(args, &opts).map_err(|message| format!("HUMANESORT_OPTS: {}", message)),
+        Err(_) => Ok(args)
+    }
+}
+
+/// Parses a buffer size such as "512M", plain numbers are kibibytes like in GNU sort.
+fn parse_size(value: &str) -> Option<usize> {
+    let (number, suffix) = value.split_at(value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len()));
+    let shift = match suffix {
+        "b" => 0,
+        "" | "k" | "K" => 10,
+        "m" | "M" => 20,
+        "g" | "G" => 30,
+        "t" | "T" => 40,
+        _ => return None
+    };
+    number.parse::<usize>().ok()?.checked_mul(1usize.checked_shl(shift)?)
+}
+
+/// Returns the order configured by `args`, lines that compare equal keep their input order.
+fn comparator(args: &Args) -> impl Fn(&[u8], &[u8]) -> Ordering + Sync + '_ {
+    let options = match args.ignore_case {
+        true => SortOptions::new().case_order(CaseOrder::Ignore),
+        false => SortOptions::new()
+    };
+    // The locale has been checked while parsing.
+    #[cfg(feature = "icu")]
+    let options = match args.locale.as_ref().and_then(|locale| Collation::new(locale).ok()) {
+        Some(collation) => options.collation(collation),
+        None => options
+    };
+    move |a: &[u8], b: &[u8]| {
+        let (a, b) = match args.reverse {
+            true => (b, a),
+            false => (a, b)
+        };
+        match args.gnu {
+            true => filevercmp::compare(a, b).then_with(|| a.cmp(b)),
+            false => text(a).humane_cmp_with(&text(b), &options)
+        }
+    }
+}
+
+/// Sorts `lines` as configured by `args`, keeping the input order of lines that compare equal.
+fn sort_lines(lines: &mut Vec<Vec<u8>>, args: &Args) {
+    let compare = comparator(args);
+    sort_by_parallel(lines, args.parallel.unwrap_or(1), |a, b| compare(a, b));
+    if args.unique {
+        lines.dedup_by(|b, a| compare(a, b) == Ordering::Equal);
+    }
+}
+
+/// Returns a line as text, invalid UTF-8 is compared as replacement characters.
+fn text(line: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(line)
+}
+
+/// Prefixes errors with the name of the file they occurred in.
+fn context<D>(name: D) -> impl FnOnce(io::Error) -> io::Error where D: fmt::Display {
+    move |err| io::Error::new(err.kind(), format!("{}: {}", name, err))
+}
+
+/// A sorted run of lines in a temporary file, which is removed again when the run is dropped.
+struct Run {
+    path: PathBuf
+}
+
+impl Run {
+    /// Creates a new empty file for a run in `dir`.
+    fn create(dir: &Path) -> io::Result<(Run, File)> {
+        let mut attempt = 0;
+        loop {
+            let path = dir.join(format!("humanesort-{}-{}", process::id(), attempt));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => return Ok((Run { path }, file)),
+                Err(ref err) if err.kind() == io::ErrorKind::AlreadyExists => attempt += 1,
+                Err(err) => return Err(context(dir.display())(err))
+            }
+        }
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// The lines read so far, in memory and in sorted runs on disk once they exceed the buffer size.
+struct Lines<'a> {
+    args: &'a Args,
+    lines: Vec<Vec<u8>>,
+    bytes: usize,
+    runs: Vec<Run>
+}
+
+impl<'a> Lines<'a> {
+    fn new(args: &'a Args) -> Self {
+        Lines { args, lines: Vec::new(), bytes: 0, runs: Vec::new() }
+    }
+
+    fn push(&mut self, line: Vec<u8>) -> io::Result<()> {
+        self.bytes += line.len() + mem::size_of::<Vec<u8>>();
+        self.lines.push(line);
+        match self.args.buffer_size {
+            Some(limit) if self.bytes >= limit => self.spill(),
+            _ => Ok(())
+        }
+    }
+
+    /// Writes the lines in memory to a new run.
+    fn spill(&mut self) -> io::Result<()> {
+        sort_lines(&mut self.lines, self.args);
+        let dir = self.args.temporary_directory.as_ref().map_or_else(env::temp_dir, PathBuf::from);
+        let (run, file) = Run::create(&dir)?;
+        let mut writer = BufWriter::new(file);
+        for line in self.lines.drain(..) {
+            writer.write_all(&line).and_then(|_| writer.write_all(b"\n")).map_err(context(run.path.display()))?;
+        }
+        writer.flush().map_err(context(run.path.display()))?;
+        self.runs.push(run);
+        self.bytes = 0;
+        Ok(())
+    }
+
+    /// Writes all lines in order to `output`.
+    fn write_sorted<W>(mut self, output: &mut W) -> io::Result<()> where W: Write {
+        if self.runs.is_empty() {
+            sort_lines(&mut self.lines, self.args);
+            return self.lines.iter().try_for_each(|line| output.write_all(line).and_then(|_| output.write_all(b"\n")))
+        }
+        if !self.lines.is_empty() {
+            self.spill()?;
+        }
+        let mut readers = Vec::new();
+        let mut heads = Vec::new();
+        for run in &self.runs {
+            let mut lines = BufReader::new(File::open(&run.path).map_err(context(run.path.display()))?).split(b'\n');
+            heads.push(lines.next().transpose().map_err(context(run.path.display()))?);
+            readers.push(lines);
+        }
+        let compare = comparator(self.args);
+        let mut last: Option<Vec<u8>> = None;
+        // The runs hold consecutive parts of the input, taking the first of equal heads keeps the
+        // merge stable. A linear scan is enough for the few runs of inputs that fit on a disk.
+        while let Some(i) = heads.iter().enumerate().filter_map(|(i, head)| head.as_ref().map(|head| (i, head)))
+            .min_by(|a, b| compare(a.1, b.1)).map(|(i, _)| i) {
+            let next = readers[i].next().transpose().map_err(context(self.runs[i].path.display()))?;
+            let line = mem::replace(&mut heads[i], next).unwrap_or_default();
+            if self.args.unique && last.as_ref().is_some_and(|last| compare(last, &line) == Ordering::Equal) {
+                continue
+            }
+            output.write_all(&line).and_then(|_| output.write_all(b"\n"))?;
+            last = Some(line);
+        }
+        Ok(())
+    }
+}
+
+/// Passes the lines of the file `name` read from `input` to `push`, without their line endings.
+fn read_lines<R, F>(input: R, name: &str, mut push: F) -> io::Result<()> where R: Read, F: FnMut(Vec<u8>) -> io::Result<()> {
+    for line in BufReader::new(input).split(b'\n') {
+        push(line.map_err(context(name))?)?;
+    }
+    Ok(())
+}
+
+/// Passes the lines of the file `name`, or of standard input for "-", to `push`.
+fn read_file<F>(name: &str, push: F) -> io::Result<()> where F: FnMut(Vec<u8>) -> io::Result<()> {
+    match name {
+        "-" => read_lines(io::stdin().lock(), name, push),
+        path => read_lines(File::open(path).map_err(context(path))?, path, push)
+    }
+}
+
+/// Returns the input files, the operands of the command line or the names read from the file of
+/// `--files0-from`. Without either the input is standard input.
+fn input_files(args: &Args) -> io::Result<Vec<String>> {
+    let from = match (&args.files0_from, args.files.is_empty()) {
+        (None, true) => return Ok(vec!["-".to_string()]),
+        (None, false) => return Ok(args.files.clone()),
+        (Some(_), false) => {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("extra operand '{}', file operands cannot be combined with --files0-from", args.files[0])))
+        }
+        (Some(from), true) => from
+    };
+    let mut names = Vec::new();
+    match from.as_str() {
+        "-" => io::stdin().lock().read_to_end(&mut names),
+        path => File::open(path).and_then(|mut file| file.read_to_end(&mut names))
+    }.map_err(context(from))?;
+    if names.is_empty() {
+        return Ok(Vec::new())
+    }
+    // The last name may or may not be ended by a NUL.
+    let names = names.strip_suffix(b"\0").unwrap_or(&names);
+    names.split(|&b| b == 0).map(|name| match name.is_empty() {
+        true => Err(io::Error::new(io::ErrorKind::InvalidData, format!("{}: invalid zero-length file name", from))),
+        false => String::from_utf8(name.to_vec()).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("{}: file name is not UTF-8", from)))
+    }).collect()
+}
+
+/// Reads the input files of `args` and writes their lines in order to `output`.
+fn sort_files<W>(args: &Args, output: &mut W) -> io::Result<()> where W: Write {
+    let mut lines = Lines::new(args);
+    for file in &input_files(args)? {
+        read_file(file, |line| lines.push(line))?;
+    }
+    lines.write_sorted(output)
+}
+
+/// A line of a `comm` input, in the order of a sort with the same options.
+struct Line<'a, F> {
+    bytes: Vec<u8>,
+    compare: &'a F
+}
+
+impl<'a, F> HumaneOrder for Line<'a, F> where F: Fn(&[u8], &[u8]) -> Ordering {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        (self.compare)(&self.bytes, &other.bytes)
+    }
+}
+
+/// Reads the lines of a `comm` input, which have to be sorted already.
+fn sorted_lines<'a, F>(name: &str, compare: &'a F) -> io::Result<Vec<Line<'a, F>>> where F: Fn(&[u8], &[u8]) -> Ordering {
+    let mut lines: Vec<Line<'a, F>> = Vec::new();
+    read_file(name, |bytes| {
+        if lines.last().is_some_and(|last| compare(&last.bytes, &bytes) == Ordering::Greater) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{}:{}: not in humane order", name, lines.len() + 1)))
+        }
+        lines.push(Line { bytes, compare });
+        Ok(())
+    })?;
+    Ok(lines)
+}
+
+/// Writes the lines only in the first file, only in the second and in both files in three columns
+/// indented by tabs, like comm does for files in byte order.
+fn comm<W>(args: &Args, output: &mut W) -> io::Result<()> where W: Write {
+    let compare = comparator(args);
+    let (first, second) = (sorted_lines(&args.files[0], &compare)?, sorted_lines(&args.files[1], &compare)?);
+    for joined in merge_join(first, second) {
+        let (column, line) = match joined {
+            EitherOrBoth::Left(line) => (0, line),
+            EitherOrBoth::Right(line) => (1, line),
+            EitherOrBoth::Both(line, _) => (2, line)
+        };
+        if args.hide[column] {
+            continue
+        }
+        let indent = args.hide[..column].iter().filter(|&&hidden| !hidden).count();
+        output.write_all(&b"\t\t"[..indent]).and_then(|_| output.write_all(&line.bytes)).and_then(|_| output.write_all(b"\n"))?;
+    }
+    Ok(())
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let stdout = io::stdout();
+    let mut output = io::BufWriter::new(stdout.lock());
+    let write = match args.command {
+        Some(Command::Comm) => comm(args, &mut output),
+        _ => sort_files(args, &mut output)
+    };
+    write.and_then(|_| output.flush()).or_else(ignore_broken_pipe).map_err(|err| err.to_string())
+}
+
+/// Treats a closed standard output, as with `humanesort | head`, as success.
+fn ignore_broken_pipe(err: io::Error) -> io::Result<()> {
+    match err.kind() {
+        io::ErrorKind::BrokenPipe => Ok(()),
+        _ => Err(err)
+    }
+}
+
+fn main() {
+    let defaults = defaults().unwrap_or_else(|message| {
+        eprintln!("humanesort: {}", message);
+        process::exit(2)
+    });
+    let args = match parse_args(defaults, env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("humanesort: {}\n\n{}", message, help::usage());
+            process::exit(2)
+        }
+    };
+    let text = match args.command {
+        Some(Command::Completions(shell)) => help::completions(shell),
+        Some(Command::Man) => help::man_page(),
+        _ if args.help => help::usage(),
+        Some(Command::Comm) | None => {
+            if let Err(message) = run(&args) {
+                eprintln!("humanesort: {}", message);
+                process::exit(2)
+            }
+            return
+        }
+    };
+    print!("{}", text);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use super::{apply_config, apply_opts, comm, parse_args, parse_size, read_lines, sort_files, sort_lines, Args, Command, Shell};
+
+    fn args(args: &[&str]) -> Result<Args, String> {
+        parse_args(Args::default(), args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_flags_and_files() {
+        let parsed = args(&["-ru", "a.txt", "--gnu", "--", "-f"]).unwrap();
+        assert_eq!(parsed, Args { gnu: true, reverse: true, unique: true, files: vec!["a.txt".into(), "-f".into()], ..Args::default() });
+        assert_eq!(args(&["-"]).unwrap().files, vec!["-".to_string()]);
+        assert!(args(&["-x"]).is_err());
+        assert!(args(&["--sort"]).is_err());
+        assert!(args(&["--gnu=yes"]).is_err());
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(args(&["completions", "zsh"]).unwrap().command, Some(Command::Completions(Shell::Zsh)));
+        assert_eq!(args(&["man"]).unwrap().command, Some(Command::Man));
+        assert!(args(&["completions"]).is_err());
+        assert!(args(&["completions", "tcsh"]).is_err());
+        assert!(args(&["man", "-r"]).is_err());
+        assert_eq!(args(&["--", "man"]).unwrap().files, vec!["man".to_string()]);
+        assert_eq!(args(&["-r", "man"]).unwrap().command, None);
+        let parsed = args(&["comm", "-13", "a", "-"]).unwrap();
+        assert_eq!(parsed, Args { command: Some(Command::Comm), hide: [true, false, true], files: vec!["a".into(), "-".into()], ..Args::default() });
+        assert!(args(&["comm", "a"]).is_err());
+        assert!(args(&["comm", "a", "b", "c"]).is_err());
+    }
+
+    #[test]
+    fn parses_values() {
+        let parsed = args(&["-uS", "2M", "--parallel=4", "-T/tmp", "--temporary-directory", "/var/tmp"]).unwrap();
+        assert_eq!(parsed, Args {
+            unique: true, buffer_size: Some(2 << 20), parallel: Some(4), temporary_directory: Some("/var/tmp".into()), ..Args::default()
+        });
+        assert_eq!(parse_size("64"), Some(64 << 10));
+        assert_eq!(parse_size("100b"), Some(100));
+        assert_eq!(parse_size("1X"), None);
+        assert!(args(&["--parallel=0"]).is_err());
+        assert!(args(&["-S"]).is_err());
+    }
+
+    #[test]
+    fn reads_defaults() {
+        let mut defaults = Args::default();
+        apply_config(&mut defaults, "preset = \"gnu\"\nignore-case = true\nreverse = false\nbuffer-size = 64\n").unwrap();
+        assert_eq!(defaults, Args { gnu: true, ignore_case: true, buffer_size: Some(64 << 10), ..Args::default() });
+        let defaults = apply_opts(defaults, " -u  --parallel 2 ").unwrap();
+        assert_eq!(defaults, Args { gnu: true, ignore_case: true, unique: true, buffer_size: Some(64 << 10), parallel: Some(2), ..Args::default() });
+        let parsed = parse_args(defaults, vec!["--preset=humane".to_string(), "-S1M".to_string()]).unwrap();
+        assert_eq!(parsed, Args { ignore_case: true, unique: true, buffer_size: Some(1 << 20), parallel: Some(2), ..Args::default() });
+        assert!(apply_config(&mut Args::default(), "sort = true").is_err());
+        assert!(apply_config(&mut Args::default(), "unique = \"yes\"").is_err());
+        assert!(apply_config(&mut Args::default(), "help = true").is_err());
+        assert!(apply_opts(Args::default(), "-r a.txt").is_err());
+        assert!(apply_opts(Args::default(), "man").is_err());
+        assert!(args(&["--preset=fast"]).is_err());
+        assert_eq!(args(&["--locale=sv"]).is_ok(), cfg!(feature = "icu"));
+    }
+
+    #[cfg(feature = "icu")]
+    #[test]
+    fn reads_posix_locales() {
+        use super::posix_locale;
+        assert_eq!(posix_locale("de_AT.UTF-8@euro").as_deref(), Some("de-AT"));
+        assert_eq!(posix_locale("sv"), Some("sv".to_string()));
+        assert_eq!(posix_locale("C.UTF-8"), None);
+        assert_eq!(posix_locale("POSIX"), None);
+    }
+
+    #[test]
+    fn sorts_lines() {
+        let mut lines = Vec::new();
+        read_lines(&b"img10\nIMG9\nimg9\nimg09\n\xFF2\n"[..], "-", |line| {
+            lines.push(line);
+            Ok(())
+        }).unwrap();
+        let sorted = |lines: &[Vec<u8>], flags: &[&str]| {
+            let mut lines = lines.to_vec();
+            sort_lines(&mut lines, &args(flags).unwrap());
+            lines.iter().map(|line| String::from_utf8_lossy(line).into_owned()).collect::<Vec<_>>()
+        };
+        assert_eq!(sorted(&lines, &[]), ["IMG9", "img9", "img09", "img10", "\u{FFFD}2"]);
+        assert_eq!(sorted(&lines, &["-f"]), ["IMG9", "img9", "img09", "img10", "\u{FFFD}2"]);
+        assert_eq!(sorted(&lines, &["-fu"]), ["IMG9", "img10", "\u{FFFD}2"]);
+        assert_eq!(sorted(&lines, &["-r"]), ["\u{FFFD}2", "img10", "img9", "img09", "IMG9"]);
+        assert_eq!(sorted(&lines, &["--gnu"]), ["IMG9", "img09", "img9", "img10", "\u{FFFD}2"]);
+        assert_eq!(sorted(&lines, &["--parallel=3", "-r"]), sorted(&lines, &["-r"]));
+    }
+
+    #[test]
+    fn reads_file_names() {
+        let dir = env::temp_dir().join(format!("humanesort-names-{}", ::std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (a, b, list) = (dir.join("a 10"), dir.join("b"), dir.join("names"));
+        fs::write(&a, "x10\nx2").unwrap();
+        fs::write(&b, "x1\n").unwrap();
+        let from = format!("--files0-from={}", list.display());
+        let sorted = |names: &str| {
+            fs::write(&list, names).unwrap();
+            let mut output = Vec::new();
+            sort_files(&args(&[&from]).unwrap(), &mut output).map(|_| String::from_utf8(output).unwrap())
+        };
+        let (a_name, b_name) = (a.display().to_string(), b.display().to_string());
+        assert_eq!(sorted(&format!("{}\0{}\0", a_name, b_name)).unwrap(), "x1\nx2\nx10\n");
+        assert_eq!(sorted(&format!("{}\0{}", b_name, a_name)).unwrap(), "x1\nx2\nx10\n");
+        assert_eq!(sorted("").unwrap(), "");
+        assert!(sorted(&format!("{}\0\0{}", a_name, b_name)).is_err());
+        let mut output = Vec::new();
+        assert!(sort_files(&args(&[&from, &a_name]).unwrap(), &mut output).is_err());
+        // Several operands are read one after another.
+        sort_files(&args(&[&b_name, &a_name]).unwrap(), &mut output).unwrap();
+        assert_eq!(output, b"x1\nx2\nx10\n");
+        for file in &[a, b, list] {
+            fs::remove_file(file).unwrap();
+        }
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn compares_sorted_files() {
+        let dir = env::temp_dir().join(format!("humanesort-comm-{}", ::std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (a, b) = (dir.join("a"), dir.join("b"));
+        fs::write(&a, "file-1\nfile-2\nfile-10\n").unwrap();
+        fs::write(&b, "FILE-2\nfile-3\nfile-10\nfile-11\n").unwrap();
+        let compared = |flags: &[&str]| {
+            let (a, b) = (a.display().to_string(), b.display().to_string());
+            let mut output = Vec::new();
+            comm(&args(&[&["comm"][..], flags, &[&a, &b]].concat()).unwrap(), &mut output).map(|_| String::from_utf8(output).unwrap())
+        };
+        assert_eq!(compared(&[]).unwrap(), "\tFILE-2\nfile-1\nfile-2\n\tfile-3\n\t\tfile-10\n\tfile-11\n");
+        assert_eq!(compared(&["-f"]).unwrap(), "file-1\n\t\tfile-2\n\tfile-3\n\t\tfile-10\n\tfile-11\n");
+        assert_eq!(compared(&["-1"]).unwrap(), "FILE-2\nfile-3\n\tfile-10\nfile-11\n");
+        assert_eq!(compared(&["-12"]).unwrap(), "file-10\n");
+        // GNU comm would take the files as unsorted.
+        assert!(compared(&["--gnu"]).is_ok());
+        assert!(compared(&["-r"]).is_err());
+        fs::remove_file(&a).unwrap();
+        fs::remove_file(&b).unwrap();
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn sorts_in_runs_on_disk() {
+        let dir = env::temp_dir().join(format!("humanesort-test-{}", ::std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input");
+        let names: Vec<String> = (0..500).map(|i| format!("file-{}\n", (i * 7919) % 250)).collect();
+        fs::write(&input, names.concat()).unwrap();
+        let sorted = |flags: &[&str]| {
+            let mut args = args(flags).unwrap();
+            args.files.push(input.to_string_lossy().into_owned());
+            let mut output = Vec::new();
+            sort_files(&args, &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let dir_flag = format!("--temporary-directory={}", dir.display());
+        for flags in &[&[][..], &["-u"], &["-r"], &["-ru"]] {
+            let in_memory = sorted(flags);
+            let on_disk = sorted(&[&["-S", "1K", dir_flag.as_str()][..], flags].concat());
+            assert_eq!(on_disk, in_memory, "{:?}", flags);
+        }
+        assert!(sorted(&["-u"]).starts_with("file-0\nfile-1\nfile-2\n"));
+        fs::remove_file(&input).unwrap();
+        // All runs have been removed again.
+        fs::remove_dir(&dir).unwrap();
+    }
+}
@@ -0,0 +1,88 @@
+//! Text comparison with the locale-specific collators of ICU4X.
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::string::{String, ToString};
+use std::sync::Arc;
+use icu_collator::{Collator, CollatorBorrowed};
+use icu_collator::options::CollatorOptions;
+use icu_locale_core::Locale;
+
+/// A collator for the text of one locale, selected with `SortOptions::collation`.
+///
+/// Creating a collator loads the locale's tailoring, clones share it.
+///
+/// ```
+/// use humanesort::{Collation, HumaneSortable, SortOptions};
+/// let swedish = SortOptions::new().collation(Collation::new("sv").unwrap());
+/// let mut names = ["ö 10", "z 1", "a 2", "ö 9"];
+/// names.humane_sort_with(&swedish);
+/// assert_eq!(names, ["a 2", "z 1", "ö 9", "ö 10"]);
+/// let german = SortOptions::new().collation(Collation::new("de").unwrap());
+/// names.humane_sort_with(&german);
+/// assert_eq!(names, ["a 2", "ö 9", "ö 10", "z 1"]);
+/// ```
+#[derive(Clone)]
+pub struct Collation {
+    locale: String,
+    collator: Arc<CollatorBorrowed<'static>>
+}
+
+impl Collation {
+    /// Creates the collator for a BCP 47 locale identifier such as "de-AT" or "sv".
+    pub fn new(locale: &str) -> Result<Collation, LocaleError> {
+        let error = || LocaleError { locale: locale.to_string() };
+        let parsed = Locale::try_from_str(locale).map_err(|_| error())?;
+        let collator = Collator::try_new((&parsed).into(), CollatorOptions::default()).map_err(|_| error())?;
+        Ok(Collation { locale: parsed.to_string(), collator: Arc::new(collator) })
+    }
+
+    /// Returns the canonical form of the locale identifier.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    pub(crate) fn compare(&self, ours: &str, theirs: &str) -> Ordering {
+        self.collator.compare(ours, theirs)
+    }
+}
+
+impl PartialEq for Collation {
+    fn eq(&self, other: &Self) -> bool {
+        self.locale == other.locale
+    }
+}
+
+impl Eq for Collation {}
+
+impl fmt::Debug for Collation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Collation").field(&self.locale).finish()
+    }
+}
+
+/// Error returned for locale identifiers that are malformed or have no collation data.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct LocaleError {
+    locale: String
+}
+
+impl fmt::Display for LocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no collator for locale {:?}", self.locale)
+    }
+}
+
+impl Error for LocaleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::Collation;
+
+    #[test]
+    fn canonical_locales() {
+        assert_eq!(Collation::new("DE-at").map(|c| c.locale().to_string()), Ok("de-AT".to_string()));
+        assert_eq!(Collation::new("de-AT").unwrap(), Collation::new("de-at").unwrap());
+        assert!(Collation::new("not a locale").is_err());
+    }
+}
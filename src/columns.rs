@@ -0,0 +1,65 @@
+//! Presentation of sorted names in columns, as `ls` does on a terminal.
+use std::string::String;
+use std::vec::Vec;
+use unicode_segmentation::UnicodeSegmentation;
+use ::HumaneSortable;
+
+/// Spaces between two columns.
+const GAP: usize = 2;
+
+/// Sorts `names` in humane order and lays them out in as many columns as fit into `width`
+/// characters.
+///
+/// Names run down the columns first, every line ends with a newline. Widths are measured in
+/// grapheme clusters, which matches the terminal for most text but not for wide characters
+/// such as CJK ideographs.
+///
+/// ```
+/// use humanesort::columns::format_columns;
+/// let names = ["img10", "img2", "img1", "notes.txt", "a"];
+/// assert_eq!(format_columns(&names, 24), "a     img2   notes.txt\nimg1  img10\n");
+/// assert_eq!(format_columns(&names, 8), "a\nimg1\nimg2\nimg10\nnotes.txt\n");
+/// ```
+pub fn format_columns<S>(names: &[S], width: usize) -> String where S: AsRef<str> {
+    let mut names: Vec<&str> = names.iter().map(AsRef::as_ref).collect();
+    names.humane_sort();
+    let lengths: Vec<usize> = names.iter().map(|name| name.graphemes(true).count()).collect();
+    let (rows, widths) = (1..names.len() + 1)
+        .map(|rows| (rows, column_widths(&lengths, rows)))
+        .find(|(_, widths)| widths.iter().sum::<usize>() + GAP * (widths.len() - 1) <= width)
+        .unwrap_or_else(|| (names.len(), vec![0]));
+    let mut output = String::new();
+    for row in 0..rows {
+        let mut columns = (row..names.len()).step_by(rows).peekable();
+        while let Some(index) = columns.next() {
+            output.push_str(names[index]);
+            if columns.peek().is_some() {
+                let padding = widths[index / rows] + GAP - lengths[index];
+                output.push_str(&" ".repeat(padding));
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Returns the width of each column when the names are split into columns of `rows` names.
+fn column_widths(lengths: &[usize], rows: usize) -> Vec<usize> {
+    lengths.chunks(rows).map(|column| column.iter().cloned().max().unwrap_or(0)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::String;
+    use std::vec::Vec;
+    use super::format_columns;
+
+    #[test]
+    fn fills_columns_first() {
+        let names: Vec<String> = (1..8).map(|i| format!("f{}", i)).collect();
+        assert_eq!(format_columns(&names, 12), "f1  f4  f7\nf2  f5\nf3  f6\n");
+        assert_eq!(format_columns(&names, 80), "f1  f2  f3  f4  f5  f6  f7\n");
+        assert_eq!(format_columns(&["é1", "e2"], 6), "e2  é1\n");
+        assert_eq!(format_columns(&Vec::<&str>::new(), 80), "");
+    }
+}
@@ -0,0 +1,115 @@
+//! Drop-in replacements for the APIs of other natural sorting crates.
+//!
+//! The functions mirror the signatures of the original crates but are implemented with this
+//! crate's ordering, so migrating is a matter of replacing the crate path. Orderings may differ
+//! from the originals in corner cases, they follow the rules documented at the crate root.
+
+/// Replacements for the `natord` crate.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use humanesort::compat::natord;
+/// assert_eq!(natord::compare("rfc822.txt", "rfc2086.txt"), Ordering::Less);
+/// assert_eq!(natord::compare_ignore_case("Abc1", "abc1"), Ordering::Equal);
+/// ```
+pub mod natord {
+    use std::cmp::Ordering;
+    use ::HumaneOrder;
+
+    /// Compares two strings humanely.
+    pub fn compare(a: &str, b: &str) -> Ordering {
+        a.humane_cmp(b)
+    }
+
+    /// Compares two strings humanely, ignoring case.
+    pub fn compare_ignore_case(a: &str, b: &str) -> Ordering {
+        a.to_lowercase().humane_cmp(&b.to_lowercase())
+    }
+}
+
+/// Replacements for the `alphanumeric-sort` crate.
+///
+/// ```
+/// use humanesort::compat::alphanumeric_sort;
+/// let mut names = ["shot-10.jpg", "shot-2.jpg", "shot-1.jpg"];
+/// alphanumeric_sort::sort_str_slice(&mut names);
+/// assert_eq!(names, ["shot-1.jpg", "shot-2.jpg", "shot-10.jpg"]);
+/// ```
+pub mod alphanumeric_sort {
+    use std::cmp::Ordering;
+    use std::path::Path;
+    use ::{HumaneOrder, HumaneSortable};
+
+    /// Compares two strings humanely.
+    pub fn compare_str<A: AsRef<str>, B: AsRef<str>>(a: A, b: B) -> Ordering {
+        a.as_ref().humane_cmp(b.as_ref())
+    }
+
+    /// Compares two paths humanely, component by component.
+    pub fn compare_path<A: AsRef<Path>, B: AsRef<Path>>(a: A, b: B) -> Ordering {
+        a.as_ref().components().humane_cmp(&b.as_ref().components())
+    }
+
+    /// Sorts a slice of string slices humanely.
+    pub fn sort_str_slice(slice: &mut [&str]) {
+        slice.humane_sort()
+    }
+
+    /// Sorts a slice of strings humanely.
+    pub fn sort_string_slice(slice: &mut [String]) {
+        slice.humane_sort()
+    }
+
+    /// Sorts a slice of paths humanely.
+    pub fn sort_path_slice<P: AsRef<Path>>(slice: &mut [P]) {
+        slice.sort_by(|a, b| compare_path(a, b))
+    }
+
+    /// Sorts a slice by a string key humanely.
+    pub fn sort_slice_by_str_key<A, T: ?Sized + AsRef<str>, F: FnMut(&A) -> &T>(slice: &mut [A], mut f: F) {
+        slice.sort_by(|a, b| f(a).as_ref().humane_cmp(f(b).as_ref()))
+    }
+}
+
+/// Replacements for the `human-sort` crate.
+///
+/// ```
+/// use humanesort::compat::human_sort;
+/// let mut names = ["item-20", "item-3"];
+/// human_sort::sort(&mut names);
+/// assert_eq!(names, ["item-3", "item-20"]);
+/// ```
+pub mod human_sort {
+    use std::cmp::Ordering;
+    use ::{HumaneOrder, HumaneSortable};
+
+    /// Sorts a slice of string slices humanely.
+    pub fn sort(arr: &mut [&str]) {
+        arr.humane_sort()
+    }
+
+    /// Compares two strings humanely.
+    pub fn compare(s1: &str, s2: &str) -> Ordering {
+        s1.humane_cmp(s2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use std::path::PathBuf;
+    use super::{alphanumeric_sort, human_sort, natord};
+
+    #[test]
+    fn shims_agree() {
+        assert_eq!(natord::compare("a2", "a10"), Ordering::Less);
+        assert_eq!(human_sort::compare("a2", "a10"), Ordering::Less);
+        assert_eq!(alphanumeric_sort::compare_str("a2", String::from("a10")), Ordering::Less);
+        let mut paths = vec![PathBuf::from("dir10/a"), PathBuf::from("dir2/b")];
+        alphanumeric_sort::sort_path_slice(&mut paths);
+        assert_eq!(paths, vec![PathBuf::from("dir2/b"), PathBuf::from("dir10/a")]);
+        let mut rows = vec![(1, "x10"), (2, "x9")];
+        alphanumeric_sort::sort_slice_by_str_key(&mut rows, |r| r.1);
+        assert_eq!(rows, vec![(2, "x9"), (1, "x10")]);
+    }
+}
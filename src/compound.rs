@@ -0,0 +1,73 @@
+//! `HumaneOrder` implementations for tuples and `Option`.
+use std::cmp::Ordering;
+use ::{EmptyOrder, HumaneOrder, SortOptions};
+
+macro_rules! impl_humane_order_for_tuple {
+    ($($name:ident $index:tt),+) => {
+        /// Tuples are ordered lexicographically, each element in humane order.
+        impl<$($name),+> HumaneOrder for ($($name,)+) where $($name: HumaneOrder),+ {
+            fn humane_cmp(&self, other: &Self) -> Ordering {
+                Ordering::Equal $(.then_with(|| self.$index.humane_cmp(&other.$index)))+
+            }
+
+            fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+                Ordering::Equal $(.then_with(|| self.$index.humane_cmp_with(&other.$index, options)))+
+            }
+        }
+    }
+}
+
+impl_humane_order_for_tuple!(A 0);
+impl_humane_order_for_tuple!(A 0, B 1);
+impl_humane_order_for_tuple!(A 0, B 1, C 2);
+impl_humane_order_for_tuple!(A 0, B 1, C 2, D 3);
+impl_humane_order_for_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_humane_order_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_humane_order_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_humane_order_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_humane_order_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_humane_order_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_humane_order_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_humane_order_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+/// `None` sorts before all values, or after them with `EmptyOrder::Last`.
+///
+/// ```
+/// use humanesort::{EmptyOrder, HumaneSortable, SortOptions};
+/// let mut names = [Some("disk-10"), None, Some("disk-9")];
+/// names.humane_sort();
+/// assert_eq!(names, [None, Some("disk-9"), Some("disk-10")]);
+/// names.humane_sort_with(&SortOptions::new().empty_order(EmptyOrder::Last));
+/// assert_eq!(names, [Some("disk-9"), Some("disk-10"), None]);
+/// ```
+impl<T> HumaneOrder for Option<T> where T: HumaneOrder {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        self.humane_cmp_with(other, &SortOptions::default())
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        match (self, other) {
+            (Some(a), Some(b)) => a.humane_cmp_with(b, options),
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) if options.empty_order == EmptyOrder::First => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => other.humane_cmp_with(self, options).reverse()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use ::{HumaneOrder, HumaneSortable, SortOptions};
+
+    #[test]
+    fn composite_keys() {
+        let mut files = [("dir-10", "a"), ("dir-9", "b2"), ("dir-9", "b10")];
+        files.humane_sort();
+        assert_eq!(files, [("dir-9", "b2"), ("dir-9", "b10"), ("dir-10", "a")]);
+        let options = SortOptions::new().decimals(true);
+        assert_eq!(("v", 1.5).humane_cmp_with(&("v", 1.25), &options), Ordering::Greater);
+        assert_eq!((Some("x7"), 2u8).humane_cmp(&(Some("x07"), 2)), Ordering::Equal);
+    }
+}
@@ -0,0 +1,157 @@
+//! Concurrent ordered maps and sets with humane keys.
+//!
+//! These wrap the lock-free skip lists of `crossbeam-skiplist`, keyed by `Box<HumaneStr>`,
+//! so multiple threads can maintain a humane-ordered index without a global lock. The wrapped
+//! collections are available through `inner` for operations not covered here.
+//!
+//! This module is only available with the `crossbeam-skiplist` feature.
+use crossbeam_skiplist::{map, set, SkipMap, SkipSet};
+use ::HumaneStr;
+
+/// A concurrent map from strings to `V`, iterated in humane order of the keys.
+///
+/// ```
+/// # extern crate humanesort;
+/// # fn main() {
+/// use humanesort::concurrent::HumaneSkipMap;
+/// let map = HumaneSkipMap::new();
+/// map.insert("node10", 10);
+/// map.insert("node9", 9);
+/// assert_eq!(*map.get("node09").unwrap().value(), 9);
+/// let keys: Vec<String> = map.iter().map(|e| e.key().to_string()).collect();
+/// assert_eq!(keys, vec!["node9", "node10"]);
+/// # }
+/// ```
+pub struct HumaneSkipMap<V> {
+    inner: SkipMap<Box<HumaneStr>, V>
+}
+
+impl<V> Default for HumaneSkipMap<V> {
+    fn default() -> Self {
+        HumaneSkipMap { inner: SkipMap::new() }
+    }
+}
+
+impl<V> HumaneSkipMap<V> where V: Send + 'static {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        HumaneSkipMap::default()
+    }
+
+    /// Inserts a value, replacing the value of a humane-equal key.
+    pub fn insert(&self, key: &str, value: V) -> map::Entry<'_, Box<HumaneStr>, V> {
+        self.inner.insert(key.into(), value)
+    }
+
+    /// Returns the entry of a key humane-equal to `key`.
+    pub fn get(&self, key: &str) -> Option<map::Entry<'_, Box<HumaneStr>, V>> {
+        self.inner.get(HumaneStr::new(key))
+    }
+
+    /// Returns `true` if the map contains a key humane-equal to `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.inner.contains_key(HumaneStr::new(key))
+    }
+
+    /// Removes the entry of a key humane-equal to `key` and returns it.
+    pub fn remove(&self, key: &str) -> Option<map::Entry<'_, Box<HumaneStr>, V>> {
+        self.inner.remove(HumaneStr::new(key))
+    }
+
+    /// Iterates over all entries in humane order of the keys.
+    pub fn iter(&self) -> map::Iter<'_, Box<HumaneStr>, V> {
+        self.inner.iter()
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the map holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the underlying skip list.
+    pub fn inner(&self) -> &SkipMap<Box<HumaneStr>, V> {
+        &self.inner
+    }
+}
+
+/// A concurrent set of strings, iterated in humane order.
+#[derive(Default)]
+pub struct HumaneSkipSet {
+    inner: SkipSet<Box<HumaneStr>>
+}
+
+impl HumaneSkipSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        HumaneSkipSet::default()
+    }
+
+    /// Inserts a string, replacing a humane-equal one.
+    pub fn insert(&self, value: &str) -> set::Entry<'_, Box<HumaneStr>> {
+        self.inner.insert(value.into())
+    }
+
+    /// Returns `true` if the set contains a string humane-equal to `value`.
+    pub fn contains(&self, value: &str) -> bool {
+        self.inner.contains(HumaneStr::new(value))
+    }
+
+    /// Removes a string humane-equal to `value` and returns it.
+    pub fn remove(&self, value: &str) -> Option<set::Entry<'_, Box<HumaneStr>>> {
+        self.inner.remove(HumaneStr::new(value))
+    }
+
+    /// Iterates over the strings in humane order.
+    pub fn iter(&self) -> set::Iter<'_, Box<HumaneStr>> {
+        self.inner.iter()
+    }
+
+    /// Returns the number of strings.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the set holds no strings.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the underlying skip list.
+    pub fn inner(&self) -> &SkipSet<Box<HumaneStr>> {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+    use super::HumaneSkipSet;
+
+    #[test]
+    fn concurrent_inserts() {
+        let set = Arc::new(HumaneSkipSet::new());
+        let handles: Vec<_> = (0..4).map(|t| {
+            let set = set.clone();
+            thread::spawn(move || {
+                for i in 0..25 {
+                    set.insert(&format!("item-{}", i * 4 + t));
+                }
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(set.len(), 100);
+        assert!(set.contains("item-007"));
+        let names: Vec<String> = set.iter().take(3).map(|e| e.value().to_string()).collect();
+        assert_eq!(names, vec!["item-0", "item-1", "item-2"]);
+        assert!(set.remove("item-99").is_some());
+        assert!(!set.contains("item-99"));
+    }
+}
@@ -0,0 +1,81 @@
+//! Recognition of the markers file managers and browsers add to duplicated file names.
+use std::borrow::Cow;
+
+/// Splits a copy marker off `name`, returning the name without it and the number of the copy.
+///
+/// The original has number 0. Recognized markers sit right before the extension:
+/// " (2)" as added by browsers, " - Copy" and " - Copy (2)" as added by Windows, and " copy"
+/// and " copy 2" as added by macOS.
+pub(crate) fn strip_copy_marker(name: &str) -> (Cow<'_, str>, u64) {
+    let (stem, extension) = split_extension(name);
+    match copy_number(stem) {
+        Some((base, number)) if extension.is_empty() => (Cow::Borrowed(base), number),
+        Some((base, number)) => (Cow::Owned(format!("{}{}", base, extension)), number),
+        None => (Cow::Borrowed(name), 0)
+    }
+}
+
+/// Splits `name` before its final dot, the extension keeps the dot.
+///
+/// A leading dot, as in ".bashrc", does not start an extension, neither does a dot followed by
+/// a space, as in "Vol. 2".
+pub(crate) fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(dot) if dot > 0 && !name[dot..].contains(' ') => name.split_at(dot),
+        _ => (name, "")
+    }
+}
+
+fn copy_number(stem: &str) -> Option<(&str, u64)> {
+    if let Some(rest) = stem.strip_suffix(')') {
+        let open = rest.rfind(" (")?;
+        let number = parse_number(&rest[open + 2..])?;
+        let base = &rest[..open];
+        return Some((strip_suffix_ignore_case(base, " - copy").unwrap_or(base), number))
+    }
+    if let Some(base) = strip_suffix_ignore_case(stem, " - copy") {
+        return Some((base, 1))
+    }
+    if let Some(base) = strip_suffix_ignore_case(stem, " copy") {
+        return Some((base, 1))
+    }
+    let space = stem.rfind(' ')?;
+    let number = parse_number(&stem[space + 1..])?;
+    strip_suffix_ignore_case(&stem[..space], " copy").map(|base| (base, number))
+}
+
+fn parse_number(digits: &str) -> Option<u64> {
+    match !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+        true => digits.parse().ok(),
+        false => None
+    }
+}
+
+fn strip_suffix_ignore_case<'a>(s: &'a str, suffix: &str) -> Option<&'a str> {
+    let start = s.len().checked_sub(suffix.len())?;
+    match s.is_char_boundary(start) && s[start..].eq_ignore_ascii_case(suffix) {
+        true => Some(&s[..start]),
+        false => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+    use super::strip_copy_marker;
+
+    #[test]
+    fn markers() {
+        let stripped = |name| {
+            let (base, number) = strip_copy_marker(name);
+            (base.into_owned(), number)
+        };
+        assert_eq!(stripped("report (3).pdf"), ("report.pdf".to_string(), 3));
+        assert_eq!(stripped("report - Copy.pdf"), ("report.pdf".to_string(), 1));
+        assert_eq!(stripped("report - Copy (2).pdf"), ("report.pdf".to_string(), 2));
+        assert_eq!(stripped("report copy 12"), ("report".to_string(), 12));
+        assert_eq!(stripped("v1.2 (1)"), ("v1.2".to_string(), 1));
+        assert_eq!(stripped("notes (draft).txt"), ("notes (draft).txt".to_string(), 0));
+        assert_eq!(stripped("copy"), ("copy".to_string(), 0));
+    }
+}
@@ -0,0 +1,144 @@
+//! Differences between two humane-sorted lists, such as successive directory snapshots.
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::vec::Vec;
+use ::HumaneOrder;
+
+/// A single step of the edit script returned by `diff_sorted`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Change<T> {
+    /// `item` was removed from position `index` of the old list.
+    Removed { index: usize, item: T },
+    /// `item` was added at position `index` of the new list.
+    Added { index: usize, item: T },
+    /// `item` moved from position `from` of the old list to position `to` of the new list,
+    /// past humane-equal items it was not in order with.
+    Moved { from: usize, to: usize, item: T }
+}
+
+/// Compares two humane-sorted lists and returns the changes that turn `old` into `new`.
+///
+/// Items are matched by humane equality, so "img7" in the old and "img07" in the new list are
+/// one item kept in place. Humane-distinct items cannot change their order in sorted lists,
+/// only items among a group of humane-equal ones can move. Within such a group identical
+/// items are matched first and as few items as possible are reported as moved. Items that are
+/// neither added, removed nor moved are kept, their indices shift implicitly.
+///
+/// ```
+/// use humanesort::diff::{diff_sorted, Change};
+/// let old = ["a1", "a2", "a10"];
+/// let new = ["a1", "a3", "a10"];
+/// assert_eq!(diff_sorted(&old, &new), vec![
+///     Change::Removed { index: 1, item: &"a2" },
+///     Change::Added { index: 1, item: &"a3" },
+/// ]);
+/// ```
+pub fn diff_sorted<'a, T>(old: &'a [T], new: &'a [T]) -> Vec<Change<&'a T>> where T: HumaneOrder + PartialEq {
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        let cmp = match (old.get(i), new.get(j)) {
+            (Some(a), Some(b)) => a.humane_cmp(b),
+            (Some(_), None) => Ordering::Less,
+            _ => Ordering::Greater
+        };
+        match cmp {
+            Ordering::Less => {
+                changes.push(Change::Removed { index: i, item: &old[i] });
+                i += 1;
+            }
+            Ordering::Greater => {
+                changes.push(Change::Added { index: j, item: &new[j] });
+                j += 1;
+            }
+            Ordering::Equal => {
+                let old_end = group_end(old, i);
+                let new_end = group_end(new, j);
+                diff_group(old, i..old_end, new, j..new_end, &mut changes);
+                i = old_end;
+                j = new_end;
+            }
+        }
+    }
+    changes
+}
+
+/// Returns the end of the run of items humane-equal to `items[start]`.
+fn group_end<T>(items: &[T], start: usize) -> usize where T: HumaneOrder {
+    start + items[start..].iter().take_while(|&item| item.humane_cmp(&items[start]) == Ordering::Equal).count()
+}
+
+/// Matches the items of two groups of humane-equal items.
+fn diff_group<'a, T>(old: &'a [T], old_range: Range<usize>, new: &'a [T], new_range: Range<usize>,
+                     changes: &mut Vec<Change<&'a T>>)
+    where T: PartialEq {
+    let mut old_of: Vec<Option<usize>> = vec![None; new_range.len()];
+    let mut used = vec![false; old_range.len()];
+    // Identical items first, then the remaining items in order.
+    for (k, slot) in old_of.iter_mut().enumerate() {
+        let exact = old_range.clone().position(|o| !used[o - old_range.start] && old[o] == new[new_range.start + k]);
+        if let Some(o) = exact {
+            used[o] = true;
+            *slot = Some(o);
+        }
+    }
+    let mut unused = (0..used.len()).filter(|&o| !used[o]).collect::<Vec<_>>().into_iter();
+    for slot in old_of.iter_mut().filter(|slot| slot.is_none()) {
+        *slot = unused.next();
+    }
+    for o in unused {
+        changes.push(Change::Removed { index: old_range.start + o, item: &old[old_range.start + o] });
+    }
+    let kept = longest_increasing(&old_of);
+    for (k, slot) in old_of.iter().enumerate() {
+        let to = new_range.start + k;
+        match *slot {
+            None => changes.push(Change::Added { index: to, item: &new[to] }),
+            Some(o) if !kept[k] => changes.push(Change::Moved { from: old_range.start + o, to, item: &new[to] }),
+            Some(_) => {}
+        }
+    }
+}
+
+/// Marks the entries forming a longest strictly increasing subsequence of the matched values.
+fn longest_increasing(values: &[Option<usize>]) -> Vec<bool> {
+    // Groups of humane-equal items are small, a quadratic search is enough.
+    let mut length = vec![0; values.len()];
+    let mut previous = vec![None; values.len()];
+    for k in 0..values.len() {
+        if let Some(value) = values[k] {
+            length[k] = 1;
+            for p in 0..k {
+                if values[p].is_some_and(|v| v < value) && length[p] + 1 > length[k] {
+                    length[k] = length[p] + 1;
+                    previous[k] = Some(p);
+                }
+            }
+        }
+    }
+    let mut kept = vec![false; values.len()];
+    let mut current = (0..values.len()).max_by_key(|&k| length[k]).filter(|&k| length[k] > 0);
+    while let Some(k) = current {
+        kept[k] = true;
+        current = previous[k];
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_sorted, Change};
+
+    #[test]
+    fn moves_within_equal_items() {
+        let old = ["x1", "x01", "x001", "y"];
+        let new = ["x01", "x1", "x001", "z"];
+        assert_eq!(diff_sorted(&old, &new), vec![
+            Change::Moved { from: 0, to: 1, item: &"x1" },
+            Change::Removed { index: 3, item: &"y" },
+            Change::Added { index: 3, item: &"z" },
+        ]);
+        assert_eq!(diff_sorted(&["a7"], &["a07"]), vec![]);
+        assert_eq!(diff_sorted(&["a", "a"], &["a"]), vec![Change::Removed { index: 1, item: &"a" }]);
+    }
+}
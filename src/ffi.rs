@@ -0,0 +1,84 @@
+//! C functions for comparing strings humanely from C and C++.
+//!
+//! The functions are exported unmangled, `include/humanesort.h` declares them. Build a shared
+//! or static library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or `--crate-type staticlib`).
+//!
+//! Strings that are not valid UTF-8 are compared with their invalid bytes replaced by U+FFFD.
+use std::cmp::Ordering;
+use std::ffi::{c_char, c_int, CStr};
+use std::slice;
+use std::string::String;
+use ::HumaneOrder;
+
+fn to_c_int(ordering: Ordering) -> c_int {
+    match ordering {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1
+    }
+}
+
+/// Returns the bytes of a NUL-terminated string, a null pointer is the empty string.
+unsafe fn c_string<'a>(s: *const c_char) -> &'a [u8] {
+    match s.is_null() {
+        true => &[],
+        false => CStr::from_ptr(s).to_bytes()
+    }
+}
+
+/// Returns `len` bytes starting at `s`, a null pointer is the empty string.
+unsafe fn bytes<'a>(s: *const c_char, len: usize) -> &'a [u8] {
+    match s.is_null() {
+        true => &[],
+        false => slice::from_raw_parts(s as *const u8, len)
+    }
+}
+
+fn compare(a: &[u8], b: &[u8]) -> c_int {
+    to_c_int(String::from_utf8_lossy(a).humane_cmp(&String::from_utf8_lossy(b)))
+}
+
+/// Compares two NUL-terminated strings like `humane_cmp`, returning -1, 0 or 1 like `strcmp`.
+///
+/// # Safety
+///
+/// `a` and `b` must be null or point to NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn humane_strcmp(a: *const c_char, b: *const c_char) -> c_int {
+    compare(c_string(a), c_string(b))
+}
+
+/// Compares two strings of the given lengths in bytes like `humane_cmp`, returning -1, 0 or 1.
+///
+/// The strings need no NUL terminator and may contain NUL bytes.
+///
+/// # Safety
+///
+/// `a` and `b` must be null or point to at least `a_len` and `b_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn humane_strcmp_len(a: *const c_char, a_len: usize, b: *const c_char, b_len: usize) -> c_int {
+    compare(bytes(a, a_len), bytes(b, b_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::c_char;
+    use std::ptr;
+    use super::{humane_strcmp, humane_strcmp_len};
+
+    #[test]
+    fn compares_c_strings() {
+        let c = |s: &'static [u8]| s.as_ptr() as *const c_char;
+        unsafe {
+            assert_eq!(humane_strcmp(c(b"file9\0"), c(b"file10\0")), -1);
+            assert_eq!(humane_strcmp(c(b"file10\0"), c(b"file9\0")), 1);
+            assert_eq!(humane_strcmp(c(b"file07\0"), c(b"file7\0")), 0);
+            assert_eq!(humane_strcmp(ptr::null(), c(b"\0")), 0);
+            assert_eq!(humane_strcmp(c(b"\xFF\0"), c(b"a\0")), 1);
+            assert_eq!(humane_strcmp_len(c(b"a2\0x"), 4, c(b"a10"), 3), -1);
+            assert_eq!(humane_strcmp_len(c(b"a2xyz"), 2, c(b"a2"), 2), 0);
+            assert_eq!(humane_strcmp_len(ptr::null(), 0, c(b"a"), 1), -1);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Version sort compatible with GNU coreutils, as used by `ls -v` and `sort -V`.
+//!
+//! This is a port of gnulib's `filevercmp` rather than a configuration of humane comparison,
+//! so orderings match the shell tools byte for byte: names are compared as bytes, `~` sorts
+//! before everything including the end of a name, and file extensions only break ties.
+use std::cmp::Ordering;
+
+/// Compares two names with the rules of gnulib's `filevercmp`.
+///
+/// The empty name sorts first, followed by ".", "..", other names with a leading dot and then
+/// all other names. Trailing extensions such as ".tar.gz" are cut off and only compared when
+/// the rest of the names are equal. Runs of digits compare by value, other bytes compare with
+/// letters before other characters and `~` before anything else.
+///
+/// Like `filevercmp`, this considers names that differ only in leading zeros equal; `sort`
+/// breaks such ties.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use humanesort::filevercmp::compare;
+/// assert_eq!(compare("app-1.10.tar.gz", "app-1.9.tar.gz"), Ordering::Greater);
+/// assert_eq!(compare("app-1.0~rc1", "app-1.0"), Ordering::Less);
+/// assert_eq!(compare("file07", "file7"), Ordering::Equal);
+/// ```
+pub fn compare<A, B>(a: A, b: B) -> Ordering where A: AsRef<[u8]>, B: AsRef<[u8]> {
+    let (a, b) = (a.as_ref(), b.as_ref());
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        (false, false) => {}
+    }
+    let rank = |name: &[u8]| match name {
+        b"." => 0,
+        b".." => 1,
+        _ if name[0] == b'.' => 2,
+        _ => 3
+    };
+    match rank(a).cmp(&rank(b)) {
+        Ordering::Equal if rank(a) >= 2 => {}
+        cmp => return cmp
+    }
+    let (a_prefix, b_prefix) = (prefix_len(a), prefix_len(b));
+    let cmp = compare_versions(&a[..a_prefix], &b[..b_prefix]);
+    match cmp == Ordering::Equal && (a_prefix != a.len() || b_prefix != b.len()) {
+        true => compare_versions(a, b),
+        false => cmp
+    }
+}
+
+/// Sorts `names` the way `ls -v` and `sort -V` in the C locale do.
+///
+/// Names `compare` considers equal are ordered by their bytes.
+///
+/// ```
+/// use humanesort::filevercmp::sort;
+/// let mut names = ["a.b", "a~", "a", "a.b~", "a1.tar.gz", ".hidden", "a01.tar.gz"];
+/// sort(&mut names);
+/// assert_eq!(names, [".hidden", "a~", "a", "a.b~", "a.b", "a01.tar.gz", "a1.tar.gz"]);
+/// ```
+pub fn sort<T>(names: &mut [T]) where T: AsRef<[u8]> {
+    names.sort_by(|a, b| compare(a, b).then_with(|| a.as_ref().cmp(b.as_ref())))
+}
+
+/// Returns the length of `name` without its suffix, the longest match of the pattern
+/// `(\.[A-Za-z~][A-Za-z0-9~]*)*$` that does not start at the first byte.
+fn prefix_len(name: &[u8]) -> usize {
+    let mut prefix = 0;
+    let mut i = 0;
+    while i < name.len() {
+        i += 1;
+        prefix = i;
+        while i + 1 < name.len() && name[i] == b'.' && (name[i + 1].is_ascii_alphabetic() || name[i + 1] == b'~') {
+            i += 2;
+            while i < name.len() && (name[i].is_ascii_alphanumeric() || name[i] == b'~') {
+                i += 1;
+            }
+        }
+    }
+    prefix
+}
+
+/// The weight of the byte at `pos` in the non-digit parts of a name, the end of the name sorting
+/// after `~` and before everything else.
+fn order(name: &[u8], pos: usize) -> i32 {
+    match name.get(pos) {
+        None => -1,
+        Some(c) if c.is_ascii_digit() => 0,
+        Some(&c) if c.is_ascii_alphabetic() => i32::from(c),
+        Some(b'~') => -2,
+        Some(&c) => i32::from(c) + 256
+    }
+}
+
+/// Compares two names as Debian version strings, alternating between non-digit and digit parts.
+fn compare_versions(a: &[u8], b: &[u8]) -> Ordering {
+    let is_digit = |name: &[u8], pos: usize| name.get(pos).is_some_and(u8::is_ascii_digit);
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        while i < a.len() && !is_digit(a, i) || j < b.len() && !is_digit(b, j) {
+            let cmp = order(a, i).cmp(&order(b, j));
+            if cmp != Ordering::Equal {
+                return cmp
+            }
+            i += 1;
+            j += 1;
+        }
+        while a.get(i) == Some(&b'0') {
+            i += 1;
+        }
+        while b.get(j) == Some(&b'0') {
+            j += 1;
+        }
+        let mut first_diff = Ordering::Equal;
+        while is_digit(a, i) && is_digit(b, j) {
+            first_diff = first_diff.then(a[i].cmp(&b[j]));
+            i += 1;
+            j += 1;
+        }
+        match (is_digit(a, i), is_digit(b, j)) {
+            (true, _) => return Ordering::Greater,
+            (_, true) => return Ordering::Less,
+            _ if first_diff != Ordering::Equal => return first_diff,
+            _ => {}
+        }
+    }
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use std::string::String;
+    use super::compare;
+
+    #[test]
+    fn gnulib_examples() {
+        // The sorted examples of gnulib's test-filevercmp.c.
+        let examples: &[&[u8]] = &[
+            b"", b".", b"..", b".0", b".9", b".A", b".Z", b".a~", b".a", b".b~", b".b", b".z", b".zz~",
+            b".zz", b".zz.~1~", b".zz.0", b".\x01", b".\x01.txt", b".\x01x", b".\x01x\x01", b".\x01.0",
+            b"0", b"9", b"A", b"Z", b"a~", b"a", b"a.b~", b"a.b", b"a.bc~", b"a.bc", b"a+", b"a.", b"a..a",
+            b"a.+", b"b~", b"b", b"gcc-c++-10.fc9.tar.gz", b"gcc-c++-10.8.12-0.7rc2.fc9.tar.bz2",
+            b"glibc-2-0.1.beta1.fc10.rpm", b"glibc-common-5-0.2.beta2.fc9.ebuild",
+            b"glibc-common-5-0.2b.deb", b"glibc-common-11b.ebuild", b"glibc-common-11-0.6rc2.ebuild",
+            b"libstdc++-0.5.8.11-0.7rc2.fc10.tar.gz", b"libstdc++-4a.fc8.tar.gz",
+            b"libstdc++-4.10.4.20040204svn.rpm", b"libstdc++-devel-3.fc8.ebuild",
+            b"libstdc++-devel-3a.fc9.tar.gz", b"libstdc++-devel-8.fc8.deb",
+            b"libstdc++-devel-8.6.2-0.4b.fc8", b"nss_ldap-1-0.2b.fc9.tar.bz2",
+            b"nss_ldap-1-0.6rc2.fc8.tar.gz", b"nss_ldap-1.0-0.1a.tar.gz", b"nss_ldap-10beta1.fc8.tar.gz",
+            b"nss_ldap-10.11.8.6.20040204cvs.fc10.ebuild", b"z", b"zz~", b"zz", b"zz.~1~", b"zz.0",
+            b"zz.0.txt", b"\x01", b"\x01.txt", b"\x01x", b"\x01x\x01", b"\x01.0", b"#\x01.b#", b"#.b#",
+        ];
+        for (i, a) in examples.iter().enumerate() {
+            for (j, b) in examples.iter().enumerate() {
+                assert_eq!(compare(a, b), i.cmp(&j), "{:?} {:?}", String::from_utf8_lossy(a), String::from_utf8_lossy(b));
+            }
+        }
+        assert_eq!(compare("a01", "a1"), Ordering::Equal);
+        assert_eq!(compare("a1.0", "a01.0"), Ordering::Equal);
+    }
+}
@@ -0,0 +1,188 @@
+//! Ordering of directory entries.
+use std::cmp::Ordering;
+use std::fs::{DirEntry, FileType};
+use std::time::SystemTime;
+use ::HumaneOrder;
+use ::key::apply_permutation;
+
+/// Secondary sort keys used when two entries have humane-equal names.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Tiebreak {
+    /// Oldest modification time first.
+    Modified,
+    /// Smallest size first.
+    Size,
+    /// Directories first, then regular files, then everything else.
+    FileType
+}
+
+/// Builder for sorting `DirEntry`s by humane file name with metadata based tiebreaks.
+///
+/// Metadata is read once per entry before sorting, never during comparisons. Entries whose
+/// metadata can't be read sort after all others for the affected tiebreak.
+///
+/// ```no_run
+/// use humanesort::fs::{DirEntrySort, Tiebreak};
+/// let mut entries: Vec<_> = std::fs::read_dir(".").unwrap().filter_map(Result::ok).collect();
+/// DirEntrySort::new().then_by(Tiebreak::Modified).sort(&mut entries);
+/// ```
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct DirEntrySort {
+    directories_first: bool,
+    tiebreaks: Vec<Tiebreak>
+}
+
+#[derive(Default)]
+struct EntryKey {
+    name: String,
+    is_dir: bool,
+    modified: Option<SystemTime>,
+    size: Option<u64>,
+    file_type: Option<u8>
+}
+
+fn file_type_rank(file_type: FileType) -> u8 {
+    if file_type.is_dir() {
+        0
+    } else if file_type.is_file() {
+        1
+    } else {
+        2
+    }
+}
+
+/// Compares `(name, is_dir)` pairs the way file managers list them, directories before files
+/// and humane order of the names within each.
+///
+/// ```
+/// use humanesort::fs::compare_directories_first;
+/// let mut entries = [("notes-10.txt", false), ("photos", true), ("notes-9.txt", false), ("2024", true)];
+/// entries.sort_by(|&a, &b| compare_directories_first(a, b));
+/// assert_eq!(entries, [("2024", true), ("photos", true), ("notes-9.txt", false), ("notes-10.txt", false)]);
+/// ```
+pub fn compare_directories_first(a: (&str, bool), b: (&str, bool)) -> Ordering {
+    b.1.cmp(&a.1).then_with(|| a.0.humane_cmp(b.0))
+}
+
+/// Orders present values before missing ones.
+fn compare_present<T>(a: &Option<T>, b: &Option<T>) -> Ordering where T: Ord {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal
+    }
+}
+
+impl DirEntrySort {
+    /// Creates a sorter ordering by humane file name only.
+    pub fn new() -> Self {
+        DirEntrySort::default()
+    }
+
+    /// Lists directories, including symbolic links to directories, before all other entries.
+    ///
+    /// Within both groups the name and the tiebreaks decide the order as usual.
+    pub fn directories_first(mut self) -> Self {
+        self.directories_first = true;
+        self
+    }
+
+    /// Adds a tiebreak, applied after the name and all previously added tiebreaks.
+    pub fn then_by(mut self, tiebreak: Tiebreak) -> Self {
+        self.tiebreaks.push(tiebreak);
+        self
+    }
+
+    /// Sorts `entries` by humane file name, then by the configured tiebreaks.
+    ///
+    /// Names that are humane-equal and tie on all tiebreaks are ordered by their raw bytes.
+    pub fn sort(&self, entries: &mut [DirEntry]) {
+        let keys: Vec<EntryKey> = entries.iter().map(|entry| self.key(entry)).collect();
+        let mut indices: Vec<usize> = (0..entries.len()).collect();
+        indices.sort_by(|&a, &b| {
+            let cmp = self.compare_keys(&keys[a], &keys[b]);
+            match cmp {
+                Ordering::Equal => entries[a].file_name().cmp(&entries[b].file_name()),
+                _ => cmp
+            }
+        });
+        apply_permutation(entries, indices);
+    }
+
+    fn key(&self, entry: &DirEntry) -> EntryKey {
+        let mut key = EntryKey {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            ..EntryKey::default()
+        };
+        let needs_metadata = self.tiebreaks.iter()
+            .any(|t| *t == Tiebreak::Modified || *t == Tiebreak::Size);
+        if needs_metadata {
+            if let Ok(metadata) = entry.metadata() {
+                key.modified = metadata.modified().ok();
+                key.size = Some(metadata.len());
+            }
+        }
+        if self.directories_first {
+            key.is_dir = match entry.file_type() {
+                Ok(file_type) => file_type.is_dir() || file_type.is_symlink() && entry.path().is_dir(),
+                Err(_) => false
+            };
+        }
+        if self.tiebreaks.contains(&Tiebreak::FileType) {
+            key.file_type = entry.file_type().ok().map(file_type_rank);
+        }
+        key
+    }
+
+    fn compare_keys(&self, a: &EntryKey, b: &EntryKey) -> Ordering {
+        let mut cmp = b.is_dir.cmp(&a.is_dir).then_with(|| a.name.humane_cmp(&b.name));
+        for tiebreak in &self.tiebreaks {
+            if cmp != Ordering::Equal {
+                break
+            }
+            cmp = match *tiebreak {
+                Tiebreak::Modified => compare_present(&a.modified, &b.modified),
+                Tiebreak::Size => compare_present(&a.size, &b.size),
+                Tiebreak::FileType => compare_present(&a.file_type, &b.file_type)
+            };
+        }
+        cmp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use super::{DirEntrySort, Tiebreak};
+
+    #[test]
+    fn sort_by_name_then_size() {
+        let dir = env::temp_dir().join(format!("humanesort-fs-{}", ::std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a10"), "").unwrap();
+        fs::write(dir.join("a7"), "large").unwrap();
+        fs::write(dir.join("a07"), "").unwrap();
+        fs::write(dir.join("a2"), "").unwrap();
+        let mut entries: Vec<_> = fs::read_dir(&dir).unwrap().map(Result::unwrap).collect();
+        DirEntrySort::new().then_by(Tiebreak::Size).sort(&mut entries);
+        let names: Vec<_> = entries.iter().map(|e| e.file_name().into_string().unwrap()).collect();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(names, vec!["a2", "a07", "a7", "a10"]);
+    }
+
+    #[test]
+    fn directories_first() {
+        let dir = env::temp_dir().join(format!("humanesort-fs-dirs-{}", ::std::process::id()));
+        fs::create_dir_all(dir.join("b10")).unwrap();
+        fs::create_dir_all(dir.join("b9")).unwrap();
+        fs::write(dir.join("a1"), "").unwrap();
+        fs::write(dir.join("c"), "").unwrap();
+        let mut entries: Vec<_> = fs::read_dir(&dir).unwrap().map(Result::unwrap).collect();
+        DirEntrySort::new().directories_first().sort(&mut entries);
+        let names: Vec<_> = entries.iter().map(|e| e.file_name().into_string().unwrap()).collect();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(names, vec!["b9", "b10", "a1", "c"]);
+    }
+}
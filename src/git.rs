@@ -0,0 +1,86 @@
+//! Sorting git tags by version.
+//!
+//! This module is only available with the `git2` feature.
+use std::cmp::Ordering;
+use libgit2::{Error, Repository};
+use ::HumaneOrder;
+
+/// Splits a tag into its version core and an optional pre-release part.
+///
+/// The pre-release part starts at the first `-` that directly follows a digit, so "v1.0.0-rc1"
+/// has the pre-release "rc1" while "release-1.0" has none.
+fn split_pre_release(tag: &str) -> (&str, Option<&str>) {
+    let bytes = tag.as_bytes();
+    for i in 1..bytes.len().saturating_sub(1) {
+        if bytes[i] == b'-' && bytes[i - 1].is_ascii_digit() {
+            return (&tag[..i], Some(&tag[i + 1..]))
+        }
+    }
+    (tag, None)
+}
+
+/// Compares version tags humanely, ordering pre-releases before the corresponding release.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use humanesort::git::compare_version_tags;
+/// assert_eq!(compare_version_tags("v1.2.9", "v1.2.10"), Ordering::Less);
+/// assert_eq!(compare_version_tags("v1.0.0-rc2", "v1.0.0"), Ordering::Less);
+/// assert_eq!(compare_version_tags("v1.0.0-rc2", "v1.0.0-rc10"), Ordering::Less);
+/// ```
+pub fn compare_version_tags(a: &str, b: &str) -> Ordering {
+    let (a_core, a_pre) = split_pre_release(a);
+    let (b_core, b_pre) = split_pre_release(b);
+    match a_core.humane_cmp(b_core) {
+        Ordering::Equal => match (a_pre, b_pre) {
+            (None, None) => Ordering::Equal,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(x), Some(y)) => x.humane_cmp(y)
+        },
+        cmp => cmp
+    }
+}
+
+/// Lists the tags of `repo` matching the glob `pattern` (all tags if `None`), sorted by
+/// version.
+///
+/// This is the equivalent of `git tag --list --sort=v:refname`.
+pub fn sorted_tags(repo: &Repository, pattern: Option<&str>) -> Result<Vec<String>, Error> {
+    let names = repo.tag_names(pattern)?;
+    let mut tags: Vec<String> = names.iter().flatten().map(String::from).collect();
+    tags.sort_by(|a, b| compare_version_tags(a, b));
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use libgit2::{Repository, Signature};
+    use super::{sorted_tags, split_pre_release};
+
+    #[test]
+    fn pre_release_split() {
+        assert_eq!(split_pre_release("v1.0.0-rc1"), ("v1.0.0", Some("rc1")));
+        assert_eq!(split_pre_release("release-1.0"), ("release-1.0", None));
+        assert_eq!(split_pre_release("v1-"), ("v1-", None));
+    }
+
+    #[test]
+    fn tags_in_version_order() {
+        let dir = env::temp_dir().join(format!("humanesort-git-{}", ::std::process::id()));
+        let repo = Repository::init(&dir).unwrap();
+        let signature = Signature::now("test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let commit_id = repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[]).unwrap();
+        let commit = repo.find_object(commit_id, None).unwrap();
+        for tag in &["v1.2.10", "v1.2.9", "v1.3.0-rc1", "v1.3.0", "v0.9"] {
+            repo.tag_lightweight(tag, &commit, false).unwrap();
+        }
+        let tags = sorted_tags(&repo, Some("v1.*"));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(tags.unwrap(), vec!["v1.2.9", "v1.2.10", "v1.3.0-rc1", "v1.3.0"]);
+    }
+}
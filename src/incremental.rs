@@ -0,0 +1,213 @@
+//! Keeping humane-sorted vectors sorted while they change.
+//!
+//! List models that react to single renames or insertions don't need to sort the whole
+//! collection again. These functions only compare the changed elements against the rest.
+use std::cmp::Ordering;
+use std::mem;
+use std::vec::Vec;
+use ::{HumaneOrder, HumaneSortable};
+
+/// Inserts `item` into the humane-sorted `items`, after all elements humane-equal to it, and
+/// returns the index it was inserted at.
+///
+/// ```
+/// use humanesort::incremental::insert_humane;
+/// let mut names = vec!["file-1", "file-3", "file-10"];
+/// assert_eq!(insert_humane(&mut names, "file-2"), 1);
+/// assert_eq!(names, vec!["file-1", "file-2", "file-3", "file-10"]);
+/// ```
+pub fn insert_humane<T>(items: &mut Vec<T>, item: T) -> usize where T: HumaneOrder {
+    let index = upper_bound(items, &item);
+    items.insert(index, item);
+    index
+}
+
+/// Restores humane order after the elements at the `dirty` indices changed, while all other
+/// elements are still sorted relative to each other.
+///
+/// Indices may be given in any order and more than once. The changed elements are sorted
+/// among themselves and then merged into the rest, which takes a number of comparisons
+/// proportional to `dirty.len() * log(items.len())` and a single pass to move the elements.
+///
+/// ```
+/// use humanesort::incremental::resort_dirty;
+/// let mut names = vec!["track-1", "track-2", "track-3", "track-10"];
+/// names[0] = "track-20";
+/// names[2] = "track-0";
+/// resort_dirty(&mut names, &[2, 0]);
+/// assert_eq!(names, vec!["track-0", "track-2", "track-10", "track-20"]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if an index is out of bounds.
+pub fn resort_dirty<T>(items: &mut Vec<T>, dirty: &[usize]) where T: HumaneOrder {
+    let mut dirty = dirty.to_vec();
+    dirty.sort_unstable();
+    dirty.dedup();
+    if let Some(&last) = dirty.last() {
+        assert!(last < items.len(), "dirty index {} out of bounds for length {}", last, items.len());
+    }
+    let mut changed = Vec::with_capacity(dirty.len());
+    let mut clean = Vec::with_capacity(items.len() - dirty.len());
+    let mut next_dirty = dirty.iter().peekable();
+    for (index, item) in mem::take(items).into_iter().enumerate() {
+        if next_dirty.peek() == Some(&&index) {
+            next_dirty.next();
+            changed.push(item);
+        } else {
+            clean.push(item);
+        }
+    }
+    changed.humane_sort();
+    // Each changed element goes after the clean elements that are humane-equal to it; the
+    // search for the next one starts where the previous one was placed.
+    let mut start = 0;
+    let bounds: Vec<usize> = changed.iter().map(|item| {
+        start += gallop(&clean[start..], |existing| existing.humane_cmp(item) != Ordering::Greater);
+        start
+    }).collect();
+    let mut clean = clean.into_iter();
+    let mut taken = 0;
+    for (item, bound) in changed.into_iter().zip(bounds) {
+        items.extend(clean.by_ref().take(bound - taken));
+        taken = bound;
+        items.push(item);
+    }
+    items.extend(clean);
+}
+
+/// Merges two humane-sorted vectors into one, placing humane-equal elements of `left` first.
+///
+/// Runs of elements taken from one side are found by galloping, an exponential search from
+/// the current position. Merging a small batch into a large vector therefore takes a number
+/// of comparisons proportional to `small * log(large)` rather than a comparison per element.
+///
+/// ```
+/// use humanesort::incremental::merge_sorted;
+/// let names = vec!["disk-1", "disk-2", "disk-10", "disk-11"];
+/// let added = vec!["disk-3", "disk-12"];
+/// assert_eq!(merge_sorted(names, added), vec!["disk-1", "disk-2", "disk-3", "disk-10", "disk-11", "disk-12"]);
+/// ```
+pub fn merge_sorted<T>(left: Vec<T>, right: Vec<T>) -> Vec<T> where T: HumaneOrder {
+    // The lengths of the runs taken alternately from the left and the right side.
+    let mut runs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        let run = gallop(&left[i..], |a| a.humane_cmp(&right[j]) != Ordering::Greater);
+        runs.push(run);
+        i += run;
+        if i == left.len() {
+            break
+        }
+        let run = gallop(&right[j..], |b| b.humane_cmp(&left[i]) == Ordering::Less);
+        runs.push(run);
+        j += run;
+    }
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let (mut left, mut right) = (left.into_iter(), right.into_iter());
+    for (k, run) in runs.into_iter().enumerate() {
+        match k % 2 {
+            0 => merged.extend(left.by_ref().take(run)),
+            _ => merged.extend(right.by_ref().take(run))
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    merged
+}
+
+/// Merges any number of humane-sorted vectors into one, placing humane-equal elements in the
+/// order of the vectors they come from.
+///
+/// Neighbouring vectors are merged pairwise with `merge_sorted` until one is left, which
+/// keeps the cost of merging a few small batches into a large vector low as well.
+///
+/// ```
+/// use humanesort::incremental::merge_sorted_runs;
+/// let runs = vec![vec!["v1", "v10"], vec!["v2"], vec!["v01", "v3"]];
+/// assert_eq!(merge_sorted_runs(runs), vec!["v1", "v01", "v2", "v3", "v10"]);
+/// ```
+pub fn merge_sorted_runs<T>(runs: Vec<Vec<T>>) -> Vec<T> where T: HumaneOrder {
+    let mut runs = runs;
+    while runs.len() > 1 {
+        let mut pairs = runs.into_iter();
+        let mut merged = Vec::with_capacity(pairs.len().div_ceil(2));
+        while let Some(left) = pairs.next() {
+            merged.push(match pairs.next() {
+                Some(right) => merge_sorted(left, right),
+                None => left
+            });
+        }
+        runs = merged;
+    }
+    runs.pop().unwrap_or_default()
+}
+
+/// Returns the number of leading elements of `items` satisfying `pred`, which has to hold
+/// for a prefix of `items` only.
+///
+/// Probes positions 1, 2, 4, 8, … before a binary search, so the cost grows with the logarithm
+/// of the result rather than of the length.
+fn gallop<T, P>(items: &[T], pred: P) -> usize where P: Fn(&T) -> bool {
+    let mut end = 1;
+    while end <= items.len() && pred(&items[end - 1]) {
+        end *= 2;
+    }
+    let start = end / 2;
+    start + items[start..(end - 1).min(items.len())].partition_point(pred)
+}
+
+/// Returns the index after the last element of the sorted `items` not greater than `item`.
+fn upper_bound<T>(items: &[T], item: &T) -> usize where T: HumaneOrder {
+    items.partition_point(|existing| existing.humane_cmp(item) != Ordering::Greater)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::cmp::Ordering;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+    use super::{insert_humane, merge_sorted, merge_sorted_runs, resort_dirty};
+    use ::{HumaneOrder, HumaneSortable};
+
+    #[test]
+    fn matches_full_sort() {
+        let mut items: Vec<String> = (0..50).map(|i| format!("n{}", i)).collect();
+        for (i, index) in [7, 3, 49, 0, 20, 3].iter().enumerate() {
+            items[*index] = format!("n{}", i * 17 % 60);
+        }
+        let mut expected = items.clone();
+        expected.humane_sort();
+        resort_dirty(&mut items, &[7, 3, 49, 0, 20, 3]);
+        assert_eq!(items, expected);
+        let mut equal = vec!["a", "b", "b"];
+        assert_eq!(insert_humane(&mut equal, "b"), 3);
+        resort_dirty(&mut equal, &[]);
+        assert_eq!(equal, vec!["a", "b", "b", "b"]);
+    }
+
+    #[test]
+    fn lopsided_merge_gallops() {
+        struct Counted<'a>(String, &'a Cell<usize>);
+        impl<'a> HumaneOrder for Counted<'a> {
+            fn humane_cmp(&self, other: &Self) -> Ordering {
+                self.1.set(self.1.get() + 1);
+                self.0.humane_cmp(&other.0)
+            }
+        }
+        let comparisons = Cell::new(0);
+        let large: Vec<Counted> = (0..10_000).map(|i| Counted(format!("n{}", i * 2), &comparisons)).collect();
+        let small: Vec<Counted> = [3, 9_001, 19_999].iter().map(|i| Counted(format!("n{}", i), &comparisons)).collect();
+        let merged = merge_sorted(large, small);
+        assert!(comparisons.get() < 100, "{} comparisons", comparisons.get());
+        let names: Vec<&str> = merged.iter().map(|item| item.0.as_str()).collect();
+        let mut expected: Vec<String> = (0..10_000).map(|i| format!("n{}", i * 2)).collect();
+        expected.extend(["n3", "n9001", "n19999"].iter().map(|s| s.to_string()));
+        expected.humane_sort();
+        assert_eq!(names, expected);
+        assert_eq!(merge_sorted_runs(vec![vec!["b"], vec![], vec!["a", "b01"], vec!["b1"]]), vec!["a", "b", "b01", "b1"]);
+        assert_eq!(merge_sorted_runs(Vec::<Vec<&str>>::new()), Vec::<&str>::new());
+    }
+}
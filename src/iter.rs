@@ -0,0 +1,532 @@
+//! Adaptors for iterators that are already in humane order, and for collecting and
+//! deduplicating iterators in humane terms.
+use std::boxed::Box;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+#[cfg(not(feature = "std"))]
+use std::collections::BTreeSet;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+use std::iter::Peekable;
+use std::vec::{self, Vec};
+use ::{HumaneOrder, HumaneSortable, HumaneSortableByKey, HumaneStr};
+
+/// An item yielded by `MergeJoin`, telling which of the inputs it came from.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum EitherOrBoth<T> {
+    /// The item only occurs in the left input.
+    Left(T),
+    /// The item only occurs in the right input.
+    Right(T),
+    /// Humane-equal items occur in both inputs.
+    Both(T, T)
+}
+
+/// Iterator returned by `merge_join`.
+pub struct MergeJoin<I, J> where I: Iterator, J: Iterator {
+    left: Peekable<I>,
+    right: Peekable<J>
+}
+
+/// Walks two humane-sorted iterators in a single pass, pairing up humane-equal items.
+///
+/// Both inputs have to be sorted with `humane_cmp` for the output to make sense.
+///
+/// ```
+/// use humanesort::iter::{merge_join, EitherOrBoth};
+/// let yesterday = vec!["file-1", "file-2", "file-10"];
+/// let today = vec!["file-2", "file-3", "file-10"];
+/// let joined: Vec<_> = merge_join(yesterday, today).collect();
+/// assert_eq!(joined, vec![
+///     EitherOrBoth::Left("file-1"),
+///     EitherOrBoth::Both("file-2", "file-2"),
+///     EitherOrBoth::Right("file-3"),
+///     EitherOrBoth::Both("file-10", "file-10"),
+/// ]);
+/// ```
+pub fn merge_join<I, J>(left: I, right: J) -> MergeJoin<I::IntoIter, J::IntoIter>
+    where I: IntoIterator, J: IntoIterator<Item = I::Item>, I::Item: HumaneOrder {
+    MergeJoin {
+        left: left.into_iter().peekable(),
+        right: right.into_iter().peekable()
+    }
+}
+
+impl<I, J> Iterator for MergeJoin<I, J>
+    where I: Iterator, J: Iterator<Item = I::Item>, I::Item: HumaneOrder {
+    type Item = EitherOrBoth<I::Item>;
+
+    fn next(&mut self) -> Option<EitherOrBoth<I::Item>> {
+        let cmp = match (self.left.peek(), self.right.peek()) {
+            (None, None) => return None,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(a), Some(b)) => a.humane_cmp(b)
+        };
+        match cmp {
+            Ordering::Less => self.left.next().map(EitherOrBoth::Left),
+            Ordering::Greater => self.right.next().map(EitherOrBoth::Right),
+            Ordering::Equal => match (self.left.next(), self.right.next()) {
+                (Some(a), Some(b)) => Some(EitherOrBoth::Both(a, b)),
+                _ => None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left_low, left_high) = self.left.size_hint();
+        let (right_low, right_high) = self.right.size_hint();
+        let high = match (left_high, right_high) {
+            (Some(a), Some(b)) => a.checked_add(b),
+            _ => None
+        };
+        (::std::cmp::max(left_low, right_low), high)
+    }
+}
+
+/// Iterator returned by `merge_humane`.
+pub struct MergeHumane<I, J> where I: Iterator, J: Iterator {
+    left: Peekable<I>,
+    right: Peekable<J>
+}
+
+/// Lazily merges two humane-sorted iterators into one humane-sorted iterator.
+///
+/// The merge is stable: of humane-equal items, those from `left` come first.
+///
+/// ```
+/// use humanesort::iter::merge_humane;
+/// let photos = vec!["img-1", "img-10"];
+/// let scans = vec!["img-2", "img-01"];
+/// let merged: Vec<_> = merge_humane(photos, scans.into_iter().rev()).collect();
+/// assert_eq!(merged, vec!["img-1", "img-01", "img-2", "img-10"]);
+/// ```
+pub fn merge_humane<I, J>(left: I, right: J) -> MergeHumane<I::IntoIter, J::IntoIter>
+    where I: IntoIterator, J: IntoIterator<Item = I::Item>, I::Item: HumaneOrder {
+    MergeHumane {
+        left: left.into_iter().peekable(),
+        right: right.into_iter().peekable()
+    }
+}
+
+impl<I, J> Iterator for MergeHumane<I, J>
+    where I: Iterator, J: Iterator<Item = I::Item>, I::Item: HumaneOrder {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let take_left = match (self.left.peek(), self.right.peek()) {
+            (Some(a), Some(b)) => a.humane_cmp(b) != Ordering::Greater,
+            (left, _) => left.is_some()
+        };
+        match take_left {
+            true => self.left.next(),
+            false => self.right.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left_low, left_high) = self.left.size_hint();
+        let (right_low, right_high) = self.right.size_hint();
+        let high = match (left_high, right_high) {
+            (Some(a), Some(b)) => a.checked_add(b),
+            _ => None
+        };
+        (left_low.saturating_add(right_low), high)
+    }
+}
+
+/// The next item of one input of `KMergeHumane`, ordered so that the heap yields the humane
+/// smallest item first, and of equal items the one from the earliest input.
+struct Head<T> {
+    item: T,
+    source: usize
+}
+
+impl<T> Ord for Head<T> where T: HumaneOrder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.item.humane_cmp(&self.item).then(other.source.cmp(&self.source))
+    }
+}
+
+impl<T> PartialOrd for Head<T> where T: HumaneOrder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> PartialEq for Head<T> where T: HumaneOrder {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Head<T> where T: HumaneOrder {}
+
+/// Iterator returned by `kmerge_humane`.
+pub struct KMergeHumane<I> where I: Iterator {
+    inputs: Vec<I>,
+    heads: BinaryHeap<Head<I::Item>>
+}
+
+/// Lazily merges any number of humane-sorted iterators into one humane-sorted iterator.
+///
+/// Every step compares O(log k) items for k inputs. The merge is stable: of humane-equal
+/// items, those from earlier inputs come first.
+///
+/// ```
+/// use humanesort::iter::kmerge_humane;
+/// let listings = vec![vec!["a/1", "a/10"], vec![], vec!["a/2", "a/9"], vec!["a/01"]];
+/// let merged: Vec<_> = kmerge_humane(listings).collect();
+/// assert_eq!(merged, vec!["a/1", "a/01", "a/2", "a/9", "a/10"]);
+/// ```
+pub fn kmerge_humane<I>(inputs: I) -> KMergeHumane<<I::Item as IntoIterator>::IntoIter>
+    where I: IntoIterator, I::Item: IntoIterator, <I::Item as IntoIterator>::Item: HumaneOrder {
+    let mut inputs: Vec<_> = inputs.into_iter().map(IntoIterator::into_iter).collect();
+    let heads = inputs.iter_mut().enumerate()
+        .filter_map(|(source, input)| input.next().map(|item| Head { item, source }))
+        .collect();
+    KMergeHumane { inputs, heads }
+}
+
+impl<I> Iterator for KMergeHumane<I> where I: Iterator, I::Item: HumaneOrder {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let head = self.heads.pop()?;
+        if let Some(item) = self.inputs[head.source].next() {
+            self.heads.push(Head { item, source: head.source });
+        }
+        Some(head.item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let heads = self.heads.len();
+        self.inputs.iter().map(Iterator::size_hint).fold((heads, Some(heads)), |(low, high), (l, h)| {
+            (low.saturating_add(l), high.and_then(|high| h.and_then(|h| high.checked_add(h))))
+        })
+    }
+}
+
+/// Which item to keep when both inputs of a set operation contain humane-equal items.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Keep {
+    /// Keep the item from the left input.
+    Left,
+    /// Keep the item from the right input.
+    Right,
+    /// Keep both items, the left one first.
+    Both
+}
+
+/// Iterator returned by `union`.
+pub struct Union<I, J> where I: Iterator, J: Iterator {
+    inner: MergeJoin<I, J>,
+    keep: Keep,
+    pending: Option<I::Item>
+}
+
+/// Iterator returned by `intersection`.
+pub struct Intersection<I, J> where I: Iterator, J: Iterator {
+    inner: MergeJoin<I, J>,
+    keep: Keep,
+    pending: Option<I::Item>
+}
+
+/// Iterator returned by `difference`.
+pub struct Difference<I, J> where I: Iterator, J: Iterator {
+    inner: MergeJoin<I, J>
+}
+
+/// Yields all items of two humane-sorted iterators in humane order.
+///
+/// Humane-equal items occurring in both inputs are resolved according to `keep`.
+/// As with `merge_join` each item is paired with at most one item of the other input.
+///
+/// ```
+/// use humanesort::iter::{union, Keep};
+/// let a = vec!["img-1", "img-007"];
+/// let b = vec!["img-7", "img-12"];
+/// assert_eq!(union(a, b, Keep::Right).collect::<Vec<_>>(), vec!["img-1", "img-7", "img-12"]);
+/// ```
+pub fn union<I, J>(left: I, right: J, keep: Keep) -> Union<I::IntoIter, J::IntoIter>
+    where I: IntoIterator, J: IntoIterator<Item = I::Item>, I::Item: HumaneOrder {
+    Union { inner: merge_join(left, right), keep, pending: None }
+}
+
+/// Yields the items of two humane-sorted iterators that occur in both of them.
+///
+/// ```
+/// use humanesort::iter::{intersection, Keep};
+/// let a = vec!["img-1", "img-007", "img-12"];
+/// let b = vec!["img-7", "img-12"];
+/// assert_eq!(intersection(a, b, Keep::Left).collect::<Vec<_>>(), vec!["img-007", "img-12"]);
+/// ```
+pub fn intersection<I, J>(left: I, right: J, keep: Keep) -> Intersection<I::IntoIter, J::IntoIter>
+    where I: IntoIterator, J: IntoIterator<Item = I::Item>, I::Item: HumaneOrder {
+    Intersection { inner: merge_join(left, right), keep, pending: None }
+}
+
+/// Yields the items of the humane-sorted `left` iterator that do not occur in `right`.
+///
+/// ```
+/// use humanesort::iter::difference;
+/// let a = vec!["img-1", "img-7", "img-12"];
+/// let b = vec!["img-7"];
+/// assert_eq!(difference(a, b).collect::<Vec<_>>(), vec!["img-1", "img-12"]);
+/// ```
+pub fn difference<I, J>(left: I, right: J) -> Difference<I::IntoIter, J::IntoIter>
+    where I: IntoIterator, J: IntoIterator<Item = I::Item>, I::Item: HumaneOrder {
+    Difference { inner: merge_join(left, right) }
+}
+
+fn resolve<T>(keep: Keep, a: T, b: T, pending: &mut Option<T>) -> T {
+    match keep {
+        Keep::Left => a,
+        Keep::Right => b,
+        Keep::Both => {
+            *pending = Some(b);
+            a
+        }
+    }
+}
+
+impl<I, J> Iterator for Union<I, J>
+    where I: Iterator, J: Iterator<Item = I::Item>, I::Item: HumaneOrder {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if let Some(item) = self.pending.take() {
+            return Some(item)
+        }
+        match self.inner.next() {
+            Some(EitherOrBoth::Left(a)) | Some(EitherOrBoth::Right(a)) => Some(a),
+            Some(EitherOrBoth::Both(a, b)) => Some(resolve(self.keep, a, b, &mut self.pending)),
+            None => None
+        }
+    }
+}
+
+impl<I, J> Iterator for Intersection<I, J>
+    where I: Iterator, J: Iterator<Item = I::Item>, I::Item: HumaneOrder {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if let Some(item) = self.pending.take() {
+            return Some(item)
+        }
+        loop {
+            match self.inner.next() {
+                Some(EitherOrBoth::Both(a, b)) => return Some(resolve(self.keep, a, b, &mut self.pending)),
+                Some(_) => continue,
+                None => return None
+            }
+        }
+    }
+}
+
+impl<I, J> Iterator for Difference<I, J>
+    where I: Iterator, J: Iterator<Item = I::Item>, I::Item: HumaneOrder {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            match self.inner.next() {
+                Some(EitherOrBoth::Left(a)) => return Some(a),
+                Some(_) => continue,
+                None => return None
+            }
+        }
+    }
+}
+
+/// Returns whether the items of an iterator are in humane order, consuming it only up to the
+/// first item that is out of order.
+///
+/// ```
+/// use humanesort::iter::is_humane_sorted;
+/// assert!(is_humane_sorted(vec!["snap-2", "snap-10", "snap-10"]));
+/// let mut read = 0;
+/// assert!(!is_humane_sorted(vec!["snap-10", "snap-2", "snap-1"].into_iter().inspect(|_| read += 1)));
+/// assert_eq!(read, 2);
+/// ```
+pub fn is_humane_sorted<I>(iter: I) -> bool where I: IntoIterator, I::Item: HumaneOrder {
+    let mut iter = iter.into_iter();
+    let mut previous = match iter.next() {
+        Some(first) => first,
+        None => return true
+    };
+    for item in iter {
+        if previous.humane_cmp(&item) == Ordering::Greater {
+            return false
+        }
+        previous = item;
+    }
+    true
+}
+
+/// Sorting adaptors for all iterators, collecting the items and returning them in humane order.
+///
+/// ```
+/// use humanesort::iter::HumaneIterator;
+/// let files = "disk-10 disk-9 disk-1".split(' ').sorted_humane();
+/// assert_eq!(files.collect::<Vec<_>>(), vec!["disk-1", "disk-9", "disk-10"]);
+/// ```
+pub trait HumaneIterator: Iterator {
+    /// Collects the items and returns them in humane order, keeping the order of humane-equal
+    /// items.
+    fn sorted_humane(self) -> vec::IntoIter<Self::Item> where Self: Sized, Self::Item: HumaneOrder {
+        let mut items: Vec<Self::Item> = self.collect();
+        items.humane_sort();
+        items.into_iter()
+    }
+
+    /// Collects the items and returns them in humane order of the key borrowed from each item
+    /// by `f`.
+    ///
+    /// ```
+    /// use humanesort::iter::HumaneIterator;
+    /// let volumes = vec![("disk-10", 3), ("disk-9", 1)].into_iter().sorted_humane_by_key(|v| v.0);
+    /// assert_eq!(volumes.map(|v| v.1).collect::<Vec<_>>(), vec![1, 3]);
+    /// ```
+    fn sorted_humane_by_key<K, F>(self, f: F) -> vec::IntoIter<Self::Item>
+        where Self: Sized, F: FnMut(&Self::Item) -> &K, K: HumaneOrder + ?Sized {
+        let mut items: Vec<Self::Item> = self.collect();
+        items.humane_sort_by_key(f);
+        items.into_iter()
+    }
+
+    /// Collects the items and returns them in humane order of the string key computed once per
+    /// item by `f`.
+    ///
+    /// ```
+    /// use humanesort::iter::HumaneIterator;
+    /// let names = vec!["Disk-10", "disk-9"].into_iter().sorted_humane_by_cached_key(|n| n.to_lowercase());
+    /// assert_eq!(names.collect::<Vec<_>>(), vec!["disk-9", "Disk-10"]);
+    /// ```
+    fn sorted_humane_by_cached_key<K, F>(self, f: F) -> vec::IntoIter<Self::Item>
+        where Self: Sized, F: FnMut(&Self::Item) -> K, K: AsRef<str> {
+        let mut items: Vec<Self::Item> = self.collect();
+        items.humane_sort_by_cached_key(f);
+        items.into_iter()
+    }
+}
+
+impl<I> HumaneIterator for I where I: Iterator {}
+
+/// Collects an iterator of results and sorts the items in humane order, returning the first
+/// error encountered instead.
+///
+/// Iteration stops at the first error, so no further input is read after a failure.
+///
+/// ```
+/// use std::io::{BufRead, Cursor};
+/// use humanesort::iter::try_sorted_humane;
+/// let input = Cursor::new("part-10\npart-9\npart-1\n");
+/// let lines = try_sorted_humane(input.lines()).unwrap();
+/// assert_eq!(lines, vec!["part-1", "part-9", "part-10"]);
+/// ```
+pub fn try_sorted_humane<I, T, E>(iter: I) -> Result<Vec<T>, E>
+    where I: IntoIterator<Item = Result<T, E>>, T: HumaneOrder {
+    let mut items = iter.into_iter().collect::<Result<Vec<T>, E>>()?;
+    items.humane_sort();
+    Ok(items)
+}
+
+/// The items seen by `UniqueHumane`, without `std` there is no `HashSet` to keep them in.
+#[cfg(feature = "std")]
+type Seen = HashSet<Box<HumaneStr>>;
+#[cfg(not(feature = "std"))]
+type Seen = BTreeSet<Box<HumaneStr>>;
+
+/// Iterator returned by `unique_humane`.
+pub struct UniqueHumane<I> {
+    iter: I,
+    seen: Seen
+}
+
+/// Removes humane-equal duplicates from an iterator in any order, keeping the first occurrence
+/// of each item and the order in which items were first seen.
+///
+/// ```
+/// use humanesort::iter::unique_humane;
+/// let input = vec!["img-10", "img-02", "img-10", "img-2", "img-1"];
+/// let unique: Vec<_> = unique_humane(input).collect();
+/// assert_eq!(unique, vec!["img-10", "img-02", "img-1"]);
+/// ```
+pub fn unique_humane<I>(iter: I) -> UniqueHumane<I::IntoIter>
+    where I: IntoIterator, I::Item: AsRef<str> {
+    UniqueHumane {
+        iter: iter.into_iter(),
+        seen: Seen::new()
+    }
+}
+
+impl<I> Iterator for UniqueHumane<I> where I: Iterator, I::Item: AsRef<str> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            let item = self.iter.next()?;
+            if !self.seen.contains(HumaneStr::new(&item)) {
+                self.seen.insert(HumaneStr::new(&item).into());
+                return Some(item)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::String;
+    use std::vec::Vec;
+    use super::{kmerge_humane, merge_humane, merge_join, union, intersection, difference, try_sorted_humane, unique_humane, EitherOrBoth, Keep};
+
+    #[test]
+    fn merge_join_uneven() {
+        let joined: Vec<_> = merge_join(vec!["a2", "a10", "b"], vec!["a10"]).collect();
+        assert_eq!(joined, vec![
+            EitherOrBoth::Left("a2"),
+            EitherOrBoth::Both("a10", "a10"),
+            EitherOrBoth::Left("b"),
+        ]);
+        assert_eq!(merge_join(Vec::<&str>::new(), vec!["x"]).collect::<Vec<_>>(), vec![EitherOrBoth::Right("x")]);
+    }
+
+    #[test]
+    fn merges_are_stable() {
+        let merged: Vec<_> = merge_humane(vec!["x1", "x01", "y"], vec!["x001", "x2"]).collect();
+        assert_eq!(merged, vec!["x1", "x01", "x001", "x2", "y"]);
+        let inputs = vec![vec!["b", "x2"], vec!["x02"], vec!["a", "x002", "z"]];
+        let merged = kmerge_humane(inputs.clone());
+        assert_eq!(merged.size_hint(), (6, Some(6)));
+        assert_eq!(merged.collect::<Vec<_>>(), vec!["a", "b", "x2", "x02", "x002", "z"]);
+        assert_eq!(kmerge_humane(Vec::<Vec<&str>>::new()).next(), None);
+    }
+
+    #[test]
+    fn set_operations_keep_both() {
+        let a = vec!["x1", "x02", "x3"];
+        let b = vec!["x2", "x4"];
+        assert_eq!(union(a.clone(), b.clone(), Keep::Both).collect::<Vec<_>>(), vec!["x1", "x02", "x2", "x3", "x4"]);
+        assert_eq!(intersection(a.clone(), b.clone(), Keep::Both).collect::<Vec<_>>(), vec!["x02", "x2"]);
+        assert_eq!(difference(b, a).collect::<Vec<_>>(), vec!["x4"]);
+    }
+
+    #[test]
+    fn try_sorted_stops_at_error() {
+        let mut consumed = 0;
+        let input = vec![Ok("b"), Err("broken"), Ok("a")].into_iter().inspect(|_| consumed += 1);
+        assert_eq!(try_sorted_humane(input), Err("broken"));
+        assert_eq!(consumed, 2);
+        let ok: Result<Vec<&str>, ()> = try_sorted_humane(vec![Ok("x10"), Ok("x2")]);
+        assert_eq!(ok, Ok(vec!["x2", "x10"]));
+    }
+
+    #[test]
+    fn unique_keeps_first_seen() {
+        let names = vec![String::from("b7"), String::from("a"), String::from("b07"), String::from("a")];
+        let unique: Vec<_> = unique_humane(names).collect();
+        assert_eq!(unique, vec!["b7", "a"]);
+        assert_eq!(unique_humane(vec!["B", "b"]).count(), 2);
+    }
+}
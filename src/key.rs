@@ -0,0 +1,628 @@
+//! Precomputed sort keys for sorting large batches of strings.
+//!
+//! Comparing two strings with `humane_cmp` tokenizes both of them, so sorting N strings
+//! tokenizes each one O(log N) times. `KeyArena` tokenizes every string exactly once and stores
+//! all token boundaries in a single flat buffer, which avoids a heap allocation per key and keeps
+//! the tokens of consecutive strings close together in memory. `HumaneKey` is the key of a
+//! single string, for storing alongside other data or sorting with `sort_by_cached_key`.
+//! `encode_key` turns a string into bytes that sort in humane order with a plain byte
+//! comparison, for the keys of ordered key-value stores, `successor` and `start_after` compute
+//! the boundaries of keyset pagination over such keys. `HumaneCache` keeps the tokens of
+//! strings across sorts, for lists that are sorted again after small changes.
+use std::boxed::Box;
+use std::cmp::Ordering;
+#[cfg(not(feature = "std"))]
+use std::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::string::String;
+use std::vec::Vec;
+#[cfg(feature = "std")]
+use parallel;
+#[cfg(feature = "std")]
+use progress::{CancelToken, Cancelled, Monitor, Progress};
+use wrapper::hash_humane;
+use ::{compare_token_streams, numeric_digits, SortingType, TokenIterator};
+
+/// Number of strings keyed between two progress reports.
+const KEYED_BATCH: usize = 4096;
+
+#[derive(Debug, Clone, Copy)]
+struct ArenaToken {
+    start: usize,
+    end: usize,
+    kind: SortingType
+}
+
+/// Records the token boundaries of `s`.
+fn tokenize(s: &str, tokens: &mut Vec<ArenaToken>) {
+    let base = s.as_ptr() as usize;
+    for (token, kind) in TokenIterator::digit_runs(s) {
+        let start = token.as_ptr() as usize - base;
+        tokens.push(ArenaToken { start, end: start + token.len(), kind });
+    }
+}
+
+fn key_tokens<'a>(s: &'a str, tokens: &'a [ArenaToken]) -> impl Iterator<Item = (&'a str, SortingType)> + 'a {
+    tokens.iter().map(move |t| (&s[t.start..t.end], t.kind))
+}
+
+/// An owned string together with its tokens, ordered like `humane_cmp` orders the string.
+///
+/// Building the key tokenizes the string once, comparisons then only compare the stored
+/// tokens. Keys that compare equal are humane-equal, and their hashes agree with those of
+/// `HumaneStr`.
+///
+/// ```
+/// use humanesort::key::HumaneKey;
+/// let mut files = vec![("b", "disk-10"), ("a", "disk-9"), ("c", "disk-1")];
+/// files.sort_by_cached_key(|file| HumaneKey::new(file.1));
+/// assert_eq!(files, vec![("c", "disk-1"), ("a", "disk-9"), ("b", "disk-10")]);
+/// assert_eq!(HumaneKey::new("disk-07"), HumaneKey::new("disk-7"));
+/// ```
+#[derive(Clone)]
+pub struct HumaneKey {
+    string: Box<str>,
+    tokens: Box<[ArenaToken]>
+}
+
+impl HumaneKey {
+    /// Tokenizes a copy of `s`.
+    pub fn new<S>(s: &S) -> HumaneKey where S: AsRef<str> + ?Sized {
+        let string: Box<str> = s.as_ref().into();
+        let mut tokens = Vec::new();
+        tokenize(&string, &mut tokens);
+        HumaneKey { string, tokens: tokens.into_boxed_slice() }
+    }
+
+    /// Returns the string the key was built from.
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+}
+
+impl fmt::Debug for HumaneKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("HumaneKey").field(&self.string).finish()
+    }
+}
+
+impl Ord for HumaneKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_token_streams(key_tokens(&self.string, &self.tokens), key_tokens(&other.string, &other.tokens))
+    }
+}
+
+impl PartialOrd for HumaneKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HumaneKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HumaneKey {}
+
+impl Hash for HumaneKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_humane(&self.string, state)
+    }
+}
+
+/// Byte ending an encoded key, below the tags of all tokens.
+const KEY_END: u8 = 0x00;
+/// Byte starting an encoded number.
+const KEY_NUMBER: u8 = 0x01;
+/// Byte starting encoded text.
+const KEY_TEXT: u8 = 0x02;
+
+/// Encodes `s` into bytes whose lexicographic order, as compared by `memcmp` or `<[u8]>::cmp`,
+/// is the order of `humane_cmp`.
+///
+/// Every token is written with a tag byte that places numbers before text. Numbers are
+/// written as the count of their digits without leading zeros, itself prefixed by its length
+/// in bytes, followed by the digits. Text is written with every zero byte escaped as `00 FF`
+/// and ends with `00 01`. Humane-equal strings, such as "disk-07" and "disk-7", have the same
+/// key.
+///
+/// ```
+/// use humanesort::key::encode_key;
+/// assert!(encode_key("disk-9") < encode_key("disk-10"));
+/// assert_eq!(encode_key("disk-07"), encode_key("disk-7"));
+/// ```
+pub fn encode_key(s: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(s.len() + 8);
+    for (token, kind) in TokenIterator::digit_runs(s) {
+        match kind {
+            SortingType::Numeric => {
+                let digits = numeric_digits(token);
+                let digits = digits.trim_start_matches('0');
+                let len = digits.len().to_be_bytes();
+                let len = &len[len.iter().take_while(|&&b| b == 0).count()..];
+                key.push(KEY_NUMBER);
+                key.push(len.len() as u8);
+                key.extend_from_slice(len);
+                key.extend_from_slice(digits.as_bytes());
+            }
+            SortingType::NonNumeric => {
+                key.push(KEY_TEXT);
+                for &b in token.as_bytes() {
+                    key.push(b);
+                    if b == 0 {
+                        key.push(0xFF);
+                    }
+                }
+                key.extend_from_slice(&[0x00, 0x01]);
+            }
+        }
+    }
+    key.push(KEY_END);
+    key
+}
+
+/// Decodes a key written by `encode_key` into a string humane-equal to the encoded one.
+///
+/// Numbers are restored as ASCII digits without leading zeros, so the string may differ from
+/// the original but encodes to the same key. Returns `None` if `key` was not written by
+/// `encode_key`, this includes well-formed tokens `encode_key` never writes, such as two
+/// numbers in a row or digits within text.
+///
+/// ```
+/// use humanesort::key::{decode_key, encode_key};
+/// assert_eq!(decode_key(&encode_key("disk-07.img")).as_deref(), Some("disk-7.img"));
+/// assert_eq!(decode_key(b"disk"), None);
+/// ```
+pub fn decode_key(key: &[u8]) -> Option<String> {
+    let mut decoded = Vec::with_capacity(key.len());
+    let mut pos = 0;
+    loop {
+        let tag = *key.get(pos)?;
+        pos += 1;
+        match tag {
+            KEY_END if pos == key.len() => break,
+            KEY_NUMBER => {
+                let width = usize::from(*key.get(pos)?);
+                let len_bytes = key.get(pos + 1..pos + 1 + width).filter(|_| width <= 8)?;
+                let len = len_bytes.iter().fold(0usize, |len, &b| len << 8 | usize::from(b));
+                pos += 1 + width;
+                let digits = key.get(pos..pos.checked_add(len)?)?;
+                if !digits.iter().all(u8::is_ascii_digit) || digits.first() == Some(&b'0') {
+                    return None
+                }
+                match digits.is_empty() {
+                    true => decoded.push(b'0'),
+                    false => decoded.extend_from_slice(digits)
+                }
+                pos += len;
+            }
+            KEY_TEXT => loop {
+                let b = *key.get(pos)?;
+                pos += 1;
+                if b != 0 {
+                    decoded.push(b);
+                    continue
+                }
+                match *key.get(pos)? {
+                    0xFF => decoded.push(0),
+                    0x01 => {
+                        pos += 1;
+                        break
+                    }
+                    _ => return None
+                }
+                pos += 1;
+            },
+            _ => return None
+        }
+    }
+    // Tokens in an order `encode_key` never writes decode to a string with a different key.
+    String::from_utf8(decoded).ok().filter(|decoded| encode_key(decoded) == key)
+}
+
+/// Returns the smallest key that sorts after `key`, the inclusive start of the page that follows
+/// the item stored at `key`.
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use humanesort::key::{encode_key, successor};
+/// let files: BTreeMap<_, _> = ["disk-1", "disk-9", "disk-10"].iter().map(|&name| (encode_key(name), name)).collect();
+/// let next: Vec<_> = files.range(successor(&encode_key("disk-9"))..).map(|(_, &name)| name).collect();
+/// assert_eq!(next, ["disk-10"]);
+/// ```
+pub fn successor(key: &[u8]) -> Vec<u8> {
+    let mut next = Vec::with_capacity(key.len() + 1);
+    next.extend_from_slice(key);
+    next.push(0x00);
+    next
+}
+
+/// Returns the smallest key that sorts after the keys of `s` and of all strings humane-equal to
+/// it, also when these keys are followed by more bytes, such as an id that keeps the keys of
+/// equal names apart.
+///
+/// No key written by `encode_key` starts with another one, so raising its last byte skips
+/// exactly the keys that start with it.
+///
+/// ```
+/// use humanesort::key::{encode_key, start_after};
+/// let key = |name: &str, id: u8| [encode_key(name), vec![id]].concat();
+/// assert!(key("disk-07", 9) < start_after("disk-7"));
+/// assert!(start_after("disk-7") <= key("disk-8", 0));
+/// ```
+pub fn start_after(s: &str) -> Vec<u8> {
+    let mut key = encode_key(s);
+    if let Some(last) = key.last_mut() {
+        *last = KEY_END + 1;
+    }
+    key
+}
+
+/// Tokenized representation of a batch of strings, stored in one flat buffer.
+///
+/// ```
+/// use humanesort::key::KeyArena;
+/// let names = vec!["file-10", "file-2", "file-1"];
+/// let arena = KeyArena::new(&names);
+/// assert_eq!(arena.sorted_indices(), vec![2, 1, 0]);
+/// ```
+pub struct KeyArena<'a> {
+    strings: Vec<&'a str>,
+    tokens: Vec<ArenaToken>,
+    offsets: Vec<usize>
+}
+
+impl<'a> KeyArena<'a> {
+    /// Tokenizes all `items` up front.
+    pub fn new<S>(items: &'a [S]) -> Self where S: AsRef<str> {
+        // Keying only stops early if the callback asks to.
+        KeyArena::build(items, |_| true).unwrap_or_else(|| unreachable!())
+    }
+
+    /// Tokenizes all `items`, passing the number of items keyed to `keyed` after every batch.
+    /// Returns `None` as soon as `keyed` returns `false`.
+    fn build<S, F>(items: &'a [S], mut keyed: F) -> Option<Self> where S: AsRef<str>, F: FnMut(usize) -> bool {
+        let mut strings = Vec::with_capacity(items.len());
+        let mut offsets = Vec::with_capacity(items.len() + 1);
+        let mut tokens = Vec::new();
+        offsets.push(0);
+        for item in items {
+            let s = item.as_ref();
+            tokenize(s, &mut tokens);
+            strings.push(s);
+            offsets.push(tokens.len());
+            let batch_done = strings.len() % KEYED_BATCH == 0 || strings.len() == items.len();
+            if batch_done && !keyed((strings.len() - 1) % KEYED_BATCH + 1) {
+                return None
+            }
+        }
+        Some(KeyArena { strings, tokens, offsets })
+    }
+
+    /// Tokenizes all `items` on the rayon thread pool.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn new_parallel<S>(items: &'a [S]) -> Self where S: AsRef<str> + Sync {
+        use librayon::prelude::*;
+        let batches: Vec<(Vec<ArenaToken>, Vec<usize>)> = items.par_chunks(KEYED_BATCH).map(|batch| {
+            let mut tokens = Vec::new();
+            let ends = batch.iter().map(|item| {
+                tokenize(item.as_ref(), &mut tokens);
+                tokens.len()
+            }).collect();
+            (tokens, ends)
+        }).collect();
+        let mut tokens = Vec::with_capacity(batches.iter().map(|batch| batch.0.len()).sum());
+        let mut offsets = Vec::with_capacity(items.len() + 1);
+        offsets.push(0);
+        for (batch_tokens, ends) in batches {
+            let base = tokens.len();
+            offsets.extend(ends.into_iter().map(|end| base + end));
+            tokens.extend(batch_tokens);
+        }
+        KeyArena { strings: items.iter().map(AsRef::as_ref).collect(), tokens, offsets }
+    }
+
+    /// Returns the number of strings in the arena.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns `true` if the arena holds no strings.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Compares the strings at index `a` and `b`, equivalent to calling `humane_cmp` on them.
+    pub fn compare(&self, a: usize, b: usize) -> Ordering {
+        compare_token_streams(self.tokens_of(a), self.tokens_of(b))
+    }
+
+    /// Returns the indices of all strings, ordered so that the strings are in humane order.
+    pub fn sorted_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.len()).collect();
+        indices.sort_by(|&a, &b| self.compare(a, b));
+        indices
+    }
+
+    fn tokens_of(&self, index: usize) -> impl Iterator<Item = (&str, SortingType)> + '_ {
+        key_tokens(self.strings[index], &self.tokens[self.offsets[index]..self.offsets[index + 1]])
+    }
+}
+
+/// Sorts `items` in humane order, tokenizing every item only once.
+///
+/// ```
+/// use humanesort::key::humane_sort_precomputed;
+/// let mut names = vec!["file-10".to_string(), "file-2".to_string(), "file-1".to_string()];
+/// humane_sort_precomputed(&mut names);
+/// assert_eq!(names, vec!["file-1", "file-2", "file-10"]);
+/// ```
+pub fn humane_sort_precomputed<S>(items: &mut [S]) where S: AsRef<str> {
+    let indices = KeyArena::new(items).sorted_indices();
+    apply_permutation(items, indices);
+}
+
+/// Sorts `items` like `humane_sort_precomputed`, reporting progress to `progress`.
+///
+/// Keying every item and sorting the keys each account for part of the reported progress.
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use humanesort::key::humane_sort_precomputed_with_progress;
+/// let mut names: Vec<String> = (0..10000).rev().map(|i| format!("file-{}", i)).collect();
+/// let keyed = AtomicUsize::new(0);
+/// humane_sort_precomputed_with_progress(&mut names, |p| keyed.store(p.keyed, Ordering::Relaxed));
+/// assert_eq!(keyed.into_inner(), 10000);
+/// assert_eq!(names[10], "file-10");
+/// ```
+#[cfg(feature = "std")]
+pub fn humane_sort_precomputed_with_progress<S, F>(items: &mut [S], progress: F)
+    where S: AsRef<str>, F: Fn(Progress) + Sync {
+    let monitor = Monitor::progress(&progress, items.len() + parallel::sort_work(items.len(), 1));
+    // A progress monitor never cancels the sort.
+    let _ = sort_monitored(items, &monitor);
+}
+
+/// Sorts `items` like `humane_sort_precomputed`, stopping early once `cancel` is set.
+///
+/// The token is checked regularly while keying and while sorting. A cancelled sort leaves
+/// `items` unchanged.
+///
+/// ```
+/// use humanesort::key::humane_sort_precomputed_cancellable;
+/// let mut names = vec!["b", "a"];
+/// assert!(humane_sort_precomputed_cancellable(&mut names, &|| true).is_err());
+/// assert_eq!(names, vec!["b", "a"]);
+/// ```
+#[cfg(feature = "std")]
+pub fn humane_sort_precomputed_cancellable<S, C>(items: &mut [S], cancel: &C) -> Result<(), Cancelled>
+    where S: AsRef<str>, C: CancelToken + Sync {
+    sort_monitored(items, &Monitor::cancel(cancel))
+}
+
+#[cfg(feature = "std")]
+fn sort_monitored<S>(items: &mut [S], monitor: &Monitor) -> Result<(), Cancelled> where S: AsRef<str> {
+    let arena = KeyArena::build(items, |count| {
+        monitor.keyed(count);
+        monitor.check().is_ok()
+    }).ok_or(Cancelled)?;
+    let mut indices: Vec<usize> = (0..arena.len()).collect();
+    parallel::sort_by_monitored(&mut indices, 1, &|&a: &usize, &b: &usize| arena.compare(a, b), Some(monitor))?;
+    apply_permutation(items, indices);
+    Ok(())
+}
+
+/// The tokens stored by `HumaneCache`, without `std` there is no `HashMap` to keep them in.
+#[cfg(feature = "std")]
+type CachedTokens = HashMap<Box<str>, (Box<[ArenaToken]>, u64)>;
+#[cfg(not(feature = "std"))]
+type CachedTokens = BTreeMap<Box<str>, (Box<[ArenaToken]>, u64)>;
+
+/// The tokens of strings kept across sorts, so strings that are sorted again are not
+/// tokenized again.
+///
+/// The cache grows with every new string it sees, `prune` drops the strings that were not part
+/// of the most recent sort.
+///
+/// ```
+/// use humanesort::key::{sort_with_cache, HumaneCache};
+/// let mut cache = HumaneCache::new();
+/// let mut files = vec!["scan-10", "scan-2"];
+/// sort_with_cache(&mut files, &mut cache);
+/// files.retain(|&file| file != "scan-2");
+/// files.push("scan-3");
+/// sort_with_cache(&mut files, &mut cache);
+/// assert_eq!(files, ["scan-3", "scan-10"]);
+/// assert_eq!(cache.len(), 3);
+/// cache.prune();
+/// assert_eq!(cache.len(), 2);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct HumaneCache {
+    tokens: CachedTokens,
+    /// The number of the most recent sort, the tokens of every string record the last sort
+    /// that used them.
+    generation: u64
+}
+
+impl HumaneCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        HumaneCache::default()
+    }
+
+    /// Returns the number of strings in the cache.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns `true` if the cache holds no strings.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Removes all strings.
+    pub fn clear(&mut self) {
+        self.tokens.clear()
+    }
+
+    /// Removes the strings that were not part of the most recent sort.
+    pub fn prune(&mut self) {
+        let generation = self.generation;
+        self.tokens.retain(|_, &mut (_, used)| used == generation)
+    }
+}
+
+/// Sorts `items` in humane order, tokenizing only the items `cache` has not seen before.
+pub fn sort_with_cache<S>(items: &mut [S], cache: &mut HumaneCache) where S: AsRef<str> {
+    sort_by_key_with_cache(items, cache, |item| item.as_ref())
+}
+
+/// Sorts `items` by the string key returned from `f`, which is called once per element and
+/// tokenized only if `cache` has not seen it before.
+///
+/// ```
+/// use humanesort::key::{sort_by_key_with_cache, HumaneCache};
+/// let mut cache = HumaneCache::new();
+/// let mut tracks = vec![(2, "track-10"), (7, "track-9")];
+/// sort_by_key_with_cache(&mut tracks, &mut cache, |track| track.1);
+/// assert_eq!(tracks, [(7, "track-9"), (2, "track-10")]);
+/// ```
+pub fn sort_by_key_with_cache<T, F>(items: &mut [T], cache: &mut HumaneCache, mut f: F) where F: FnMut(&T) -> &str {
+    cache.generation += 1;
+    let generation = cache.generation;
+    let indices = {
+        let keys: Vec<&str> = items.iter().map(&mut f).collect();
+        for &key in &keys {
+            match cache.tokens.get_mut(key) {
+                Some(entry) => entry.1 = generation,
+                None => {
+                    let mut tokens = Vec::new();
+                    tokenize(key, &mut tokens);
+                    cache.tokens.insert(key.into(), (tokens.into_boxed_slice(), generation));
+                }
+            }
+        }
+        let tokens: Vec<&[ArenaToken]> = keys.iter().map(|&key| &*cache.tokens[key].0).collect();
+        let mut indices: Vec<usize> = (0..keys.len()).collect();
+        indices.sort_by(|&a, &b| compare_token_streams(key_tokens(keys[a], tokens[a]), key_tokens(keys[b], tokens[b])));
+        indices
+    };
+    apply_permutation(items, indices);
+}
+
+/// Reorders `items` so that `items[i]` becomes the element previously found at `indices[i]`.
+pub(crate) fn apply_permutation<T>(items: &mut [T], mut indices: Vec<usize>) {
+    for i in 0..items.len() {
+        let mut current = i;
+        while indices[current] != i {
+            let next = indices[current];
+            items.swap(current, next);
+            indices[current] = current;
+            current = next;
+        }
+        indices[current] = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use std::collections::BTreeMap;
+    use std::vec::Vec;
+    use super::{decode_key, encode_key, humane_sort_precomputed, sort_with_cache, start_after, successor, HumaneCache, HumaneKey, KeyArena};
+    use ::{HumaneOrder, HumaneSortable};
+
+    #[test]
+    fn matches_humane_sort() {
+        let mut expected = vec!["b", "a10", "a2", "", "10", "9b", "a2b", "a", "é1", "e1"];
+        let mut actual = expected.clone();
+        expected.humane_sort();
+        humane_sort_precomputed(&mut actual);
+        assert_eq!(expected, actual);
+        let arena = KeyArena::new(&actual);
+        assert_eq!(arena.len(), actual.len());
+        assert_eq!(arena.sorted_indices(), (0..actual.len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn key_order_matches_humane_cmp() {
+        let names = ["b", "a10", "a2", "", "10", "9b", "a2b", "a", "é1", "e1", "a02"];
+        for a in &names {
+            for b in &names {
+                assert_eq!(HumaneKey::new(a).cmp(&HumaneKey::new(b)), a.humane_cmp(b), "{} {}", a, b);
+            }
+        }
+        assert_eq!(HumaneKey::new("x7").clone().as_str(), "x7");
+    }
+
+    #[test]
+    fn encoded_keys_round_trip() {
+        let long = format!("n{}", "9".repeat(300));
+        let names = ["", "a", "a\0", "a\0b", "a\u{1}", "a1", "a01", "a0", "a10", "a\0 1", "٣a", "1\u{FE0F}\u{20E3}",
+                     "10", "9b", "b", "é1", "\u{10FFFF}", long.as_str(), "n1000"];
+        for a in &names {
+            let key = encode_key(a);
+            let decoded = decode_key(&key).unwrap();
+            assert_eq!(decoded.as_str().humane_cmp(a), Ordering::Equal, "{:?}", a);
+            assert_eq!(encode_key(&decoded), key);
+            for b in &names {
+                assert_eq!(key.cmp(&encode_key(b)), a.humane_cmp(b), "{:?} {:?}", a, b);
+            }
+        }
+        for invalid in [&b"\x02a"[..], b"\x01\x01\x01a\x00", b"\x01\x01\x01\x30\x00", b"\x00\x00", b"\x02\xFF\x00\x01\x00",
+                        b"\x01\x01\x01\x31\x01\x01\x01\x32\x00", b"\x02a1\x00\x01\x00", b"\x02\x00\x01\x00",
+                        b"\x02a\x00\x01\x02b\x00\x01\x00"] {
+            assert_eq!(decode_key(invalid), None, "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn pages_neither_skip_nor_repeat() {
+        let mut names = vec!["b", "a10", "a2", "", "10", "9b", "a2b", "a", "é1", "e1", "a\0", "a\0b", "a9", "n1000", "a1"];
+        let store: BTreeMap<Vec<u8>, &str> = names.iter().map(|&name| (encode_key(name), name)).collect();
+        names.humane_sort();
+        for size in 1..5 {
+            let mut listed = Vec::new();
+            let mut start = Vec::new();
+            loop {
+                let page: Vec<_> = store.range(start.clone()..).take(size).collect();
+                match page.last() {
+                    Some(&(key, _)) => start = successor(key),
+                    None => break
+                }
+                listed.extend(page.iter().map(|&(_, &name)| name));
+            }
+            assert_eq!(listed, names, "{}", size);
+        }
+        // Keys followed by an id keep humane-equal names apart, a page after a name skips all of them.
+        let store: BTreeMap<Vec<u8>, &str> = ["a7", "a07", "a8", "a70", "a7b"].iter().enumerate()
+            .map(|(id, &name)| ([encode_key(name), vec![id as u8]].concat(), name)).collect();
+        let after = |name: &str| store.range(start_after(name)..).map(|(_, &name)| name).collect::<Vec<_>>();
+        assert_eq!(after("a7"), ["a7b", "a8", "a70"]);
+        assert_eq!(after("a007"), ["a7b", "a8", "a70"]);
+        assert_eq!(after("a6"), ["a7", "a07", "a7b", "a8", "a70"]);
+        assert_eq!(after("a70"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn cache_matches_humane_sort() {
+        let mut cache = HumaneCache::new();
+        let mut expected = vec!["b", "a10", "a2", "", "10", "9b", "a2b", "a", "é1", "e1", "a2"];
+        for round in 0..3 {
+            let mut actual = expected.clone();
+            actual.rotate_left(round);
+            expected.humane_sort();
+            sort_with_cache(&mut actual, &mut cache);
+            assert_eq!(expected, actual);
+        }
+        assert_eq!(cache.len(), 10);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
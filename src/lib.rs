@@ -7,7 +7,7 @@
 //! Often this is not the desired behavior, this crate implements a more human compatible ordering
 //! by treating each occurrence of consecutive digits as a combined number in sorting.
 //!
-//! The crate implements the type `HumaneOrder` for common types (currently only most string types) and `HumaneSortable` for slices of
+//! The crate implements the type `HumaneOrder` for common types (string types, paths and numbers) and `HumaneSortable` for slices and arrays of
 //! `HumanOrder` types.
 //!
 //! The API is very simple to use:
@@ -19,6 +19,12 @@
 //! assert_eq!(vec!["something-1", "something-2", "something-11"], sort_me);
 //! ```
 //!
+//! Earlier versions implemented `HumaneOrder` for every type implementing `AsRef<str>`. This
+//! blanket implementation was replaced by implementations for the standard string types, so that
+//! paths, numbers, tuples and `Option` can implement it too. Other types that only implement
+//! `AsRef<str>` can delegate to `str` in their own implementation, or be sorted by the string they
+//! refer to with `humane_sort_by_key`.
+//!
 //! ## Details on String Sorting
 //!
 //! For sorting, a string is split into numeric and non-numeric sections.
@@ -27,8 +33,8 @@
 //! used.
 //!
 //! * Any non-numbers are compared using their usual compare methods
-//! * Numbers are always greater than non-numbers
-//! * Numeric sequences are ordered by their numeric value
+//! * Numbers are smaller than non-numbers, `SortOptions::number_order` can make them greater
+//! * Numeric sequences are ordered by their numeric value, digits of other scripts ("٣", "３") and keycap emoji ("1️⃣", "🔟") count as digits
 //! * Empty sequences are always smaller than non-empty ones
 //!
 //!
@@ -47,23 +53,225 @@
 //! a.humane_sort();
 //! assert_eq!(a, ["1-ffff", "12-aaaa", "13-zzzz"])
 //! ```
+//!
+//! ```
+//! use humanesort::HumaneSortable;
+//! let mut a = ["intro", "2 verse", "10 outro"];
+//! a.humane_sort();
+//! assert_eq!(a, ["2 verse", "10 outro", "intro"])
+//! ```
+//!
+//! ## Options
+//!
+//! The rules above can be adjusted with `SortOptions`, passed to `humane_sort_with` and
+//! `humane_cmp_with`:
+//!
+//! ```
+//! use humanesort::{CaseOrder, HumaneSortable, SortOptions};
+//! let mut a = ["apple", "Banana", "Apple"];
+//! a.humane_sort_with(&SortOptions::new().case_order(CaseOrder::LowerFirst));
+//! assert_eq!(a, ["apple", "Apple", "Banana"])
+//! ```
+//!
+//! ## Ordered Collections
+//!
+//! `HumaneString` and `HumaneStr` wrap owned and borrowed strings and implement `Ord`, `Eq` and
+//! `Hash` humanely, so they can be the keys of `BTreeMap`, `BTreeSet` and `BinaryHeap`:
+//!
+//! ```
+//! use std::collections::BTreeSet;
+//! use humanesort::prelude::*;
+//! let mut versions = BTreeSet::new();
+//! versions.insert(HumaneString::from("v1.10"));
+//! versions.insert(HumaneString::from("v1.9"));
+//! assert!(versions.contains(HumaneStr::new("v1.09")));
+//! assert_eq!(versions.iter().map(HumaneString::as_str).collect::<Vec<_>>(), ["v1.9", "v1.10"]);
+//! ```
+//!
+//! ## Optional Features
+//!
+//! * `any_ascii`: comparison of text by its ASCII transliteration, enabled with
+//!   `SortOptions::transliterate`
+//! * `cli`: the `humanesort` command, which writes the lines of files or standard input in
+//!   humane order, `humanesort comm` compares two sorted files, `humanesort completions bash|zsh|fish`
+//!   and `humanesort man` write its shell completions and manual page, defaults are read from `HUMANESORT_OPTS` and a TOML config file
+//! * `crossbeam-skiplist`: concurrent maps and sets with humane keys, in the `concurrent` module
+//! * `derive`: `#[derive(HumaneOrder)]` for structs, comparing their fields in order
+//! * `ffi`: `humane_strcmp` and `humane_strcmp_len` for C and C++, in the `ffi` module
+//! * `git2`: sorting of git tags by version, in the `git` module
+//! * `icu`: text comparison with the ICU4X collator of a locale, enabled with
+//!   `SortOptions::collation`
+//! * `jwalk`: parallel directory traversal in humane order, in the `walk` module
+//! * `native-collation`: text comparison with the Windows or macOS collator, enabled with
+//!   `SortOptions::native_collation`
+//! * `rayon`: parallel sorting on the rayon thread pool, in the `rayon` module
+//! * `serde`: transparent `Serialize` and `Deserialize` for the wrapper types
+//! * `simd`: SSE2 or NEON scanning of digit runs and equal prefixes of ASCII names, for bulk
+//!   sorting
+//! * `std` (default): the modules that need the operating system, see below
+//! * `testing`: helpers for checking custom comparators, in the `testing` module
+//! * `toml_edit`: humane reordering of TOML documents, in the `toml` module
+//! * `wasm`: `compare` and `sort` for JavaScript through `wasm-bindgen`, in the `wasm` module
+//! * `yaml`: humane ordering of YAML mapping keys, in the `yaml` module
+//!
+//! ## `no_std`
+//!
+//! The default `std` feature can be disabled to use the crate with `core` and `alloc` only.
+//! Comparisons, sorting of slices, the wrapper types and the modules that do not need the
+//! operating system keep working, the `compat`, `fs`, `parallel`, `path` and `progress`
+//! modules and the features that build on them require `std`.
+#![cfg_attr(not(feature = "std"), no_std)]
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+extern crate unicode_normalization;
 extern crate unicode_segmentation;
+#[cfg(feature = "git2")]
+extern crate git2 as libgit2;
+#[cfg(feature = "toml_edit")]
+extern crate toml_edit;
+#[cfg(any(feature = "serde", feature = "yaml"))]
+extern crate serde;
+#[cfg(feature = "yaml")]
+extern crate serde_yaml;
+#[cfg(feature = "jwalk")]
+extern crate jwalk;
+#[cfg(feature = "crossbeam-skiplist")]
+extern crate crossbeam_skiplist;
+#[cfg(feature = "derive")]
+extern crate humanesort_derive;
+#[cfg(feature = "any_ascii")]
+extern crate any_ascii;
+#[cfg(feature = "icu")]
+extern crate icu_collator;
+#[cfg(feature = "icu")]
+extern crate icu_locale_core;
+#[cfg(feature = "rayon")]
+extern crate rayon as librayon;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
 pub mod prelude;
+pub mod columns;
+#[cfg(feature = "std")]
+pub mod compat;
+#[cfg(feature = "crossbeam-skiplist")]
+pub mod concurrent;
+pub mod diff;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod filevercmp;
+#[cfg(feature = "std")]
+pub mod fs;
+#[cfg(feature = "git2")]
+pub mod git;
+pub mod incremental;
+pub mod iter;
+pub mod key;
+pub mod m3u;
+pub mod metrics;
+pub mod multikey;
+#[cfg(feature = "std")]
+pub mod parallel;
+#[cfg(feature = "std")]
+pub mod path;
+#[cfg(feature = "std")]
+pub mod progress;
+pub mod properties;
+#[cfg(feature = "rayon")]
+pub mod rayon;
+pub mod sections;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod tokenize;
+#[cfg(feature = "toml_edit")]
+pub mod toml;
+pub mod value;
+#[cfg(feature = "jwalk")]
+pub mod walk;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+#[cfg(feature = "icu")]
+mod collation;
+mod compound;
+mod copies;
+mod digits;
+#[cfg(feature = "native-collation")]
+mod native;
+mod options;
+mod primitive;
+mod scan;
+mod timestamp;
+mod token;
+mod wrapper;
+use std::borrow::Cow;
+use std::boxed::Box;
 use std::iter::Peekable;
+use std::rc::Rc;
+use std::string::String;
+use std::sync::Arc;
+use std::vec::Vec;
 use unicode_segmentation::{GraphemeIndices, UnicodeSegmentation};
 use std::cmp::Ordering;
+pub use options::{CaseOrder, EmptyOrder, ExtensionOrder, HexNumbers, Limits, NanOrder, NegativeNumbers, NumberOrder, SortOptions, Whitespace, ZeroOrder};
+pub use wrapper::{ByDisplay, HumaneReverse, HumaneStr, HumaneString};
+#[cfg(feature = "icu")]
+pub use collation::{Collation, LocaleError};
+/// Derives `HumaneOrder` for a struct by comparing its fields in declaration order.
+///
+/// Fields marked `#[humane(skip)]` are left out. When any field is marked `#[humane(key)]`,
+/// only the key fields are compared.
+///
+/// ```
+/// # extern crate humanesort;
+/// use humanesort::{HumaneOrder, HumaneSortable};
+///
+/// #[derive(HumaneOrder, Debug, PartialEq)]
+/// struct Track {
+///     #[humane(key)]
+///     title: String,
+///     plays: u32
+/// }
+///
+/// # fn main() {
+/// let track = |title: &str, plays| Track { title: title.to_string(), plays };
+/// let mut tracks = [track("part 10", 1), track("part 9", 7)];
+/// tracks.humane_sort();
+/// assert_eq!(tracks, [track("part 9", 7), track("part 10", 1)]);
+/// # }
+/// ```
+#[cfg(feature = "derive")]
+pub use humanesort_derive::HumaneOrder;
+
+/// Items used by the code `#[derive(HumaneOrder)]` generates.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use std::cmp::Ordering;
+}
+
+/// The parts of `std` this crate uses, provided by `core` and `alloc` without the `std`
+/// feature.
+#[cfg(not(feature = "std"))]
+mod std {
+    pub use core::*;
+    pub use alloc::{borrow, boxed, collections, fmt, rc, slice, string, sync, vec};
+}
 
 #[cfg(test)]
 mod tests {
+    use std::vec::Vec;
+
     #[test]
     fn sorting_test() {
         use ::SortingType;
         let s = "11LOL";
         let fun = &|x: &str| -> SortingType {
-            if x.chars().all(|c| char::is_numeric(c)) {
-                return SortingType::Numeric
+            if x.chars().all(char::is_numeric) {
+                SortingType::Numeric
             } else {
-                return SortingType::NonNumeric
+                SortingType::NonNumeric
             }
         };
         let mut it = ::TokenIterator::new(s, fun);
@@ -71,6 +279,38 @@ mod tests {
         assert_eq!(it.next().unwrap().0, "LOL");
     }
 
+    #[test]
+    fn ascii_tokens_match_graphemes() {
+        use ::{sorting_type, TokenIterator};
+        for s in &["", "a", "7", "file-10.txt", "12ab34\r\n5", "\r\n\r\n", " 0x1F ", "holiday-photos-2023-08-14_000123456789.jpeg"] {
+            let ascii: Vec<_> = TokenIterator::new(s, &sorting_type).collect();
+            let graphemes: Vec<_> = TokenIterator::with_graphemes(s, &sorting_type).collect();
+            assert_eq!(ascii, graphemes, "{:?}", s);
+            assert_eq!(TokenIterator::digit_runs(s).collect::<Vec<_>>(), graphemes, "{:?}", s);
+        }
+        let vowel = |x: &str| "aeiou".contains(x);
+        let ascii: Vec<_> = TokenIterator::new("queueing 42", &vowel).collect();
+        assert_eq!(ascii, TokenIterator::with_graphemes("queueing 42", &vowel).collect::<Vec<_>>());
+        assert_eq!(ascii[1], ("ueuei", true));
+    }
+
+    #[test]
+    fn skipped_prefixes_keep_order() {
+        use std::cmp::Ordering;
+        use ::{compare_token_streams, equal_prefix, sorting_type, HumaneOrder, TokenIterator};
+        let names = ["", "a", "ab1", "abc", "abd", "a9", "a10", "a010", "x12y", "x123", "IMG_2023_0001_final", "IMG_2023_0001_finalist",
+                     "IMG_2023_0001_final2", "IMG_2023_00010", "äb1"];
+        for a in &names {
+            for b in &names {
+                let full = compare_token_streams(TokenIterator::new(a, &sorting_type), TokenIterator::new(b, &sorting_type));
+                assert_eq!(a.humane_cmp(b), full, "{:?} {:?}", a, b);
+            }
+        }
+        assert_eq!(equal_prefix("IMG_2023_0001_final", "IMG_2023_00010"), 9);
+        assert_eq!(equal_prefix("äb1", "äb2"), 0);
+        assert_eq!("a010".humane_cmp("a10"), Ordering::Equal);
+    }
+
     #[test]
     fn sort_slice() {
         use HumaneSortable;
@@ -81,84 +321,552 @@ mod tests {
         sort_me.humane_sort();
         assert_eq!(vec!["something-1", "something-2", "something-11"], sort_me);
     }
+
+    #[test]
+    fn sort_array() {
+        use HumaneSortable;
+        fn sort_generic<S: HumaneSortable + ?Sized>(s: &mut S) {
+            s.humane_sort();
+        }
+        let mut names = ["track-10", "track-9", "track-1"];
+        sort_generic(&mut names);
+        assert_eq!(["track-1", "track-9", "track-10"], names);
+    }
+
+    #[test]
+    fn long_numbers() {
+        use HumaneSortable;
+        let mut ids = vec!["id-123456789012345678901234567890", "id-99999999999999999999", "id-0000000000000000000000000000000000000001", "id-0"];
+        ids.humane_sort();
+        assert_eq!(ids, vec!["id-0", "id-0000000000000000000000000000000000000001", "id-99999999999999999999", "id-123456789012345678901234567890"]);
+    }
+
+    #[test]
+    fn unicode_digits() {
+        use HumaneSortable;
+        let mut names = vec!["ファイル１０", "ファイル２", "صفحة ١٢", "صفحة ٣", "पाठ १०", "पाठ ९"];
+        names.humane_sort();
+        assert_eq!(names, vec!["صفحة ٣", "صفحة ١٢", "पाठ ९", "पाठ १०", "ファイル２", "ファイル１０"]);
+    }
+
+    #[test]
+    fn keycap_digits() {
+        use HumaneSortable;
+        let mut names = vec!["🔟 outro", "2\u{20E3} middle", "1️⃣1️⃣ bonus", "1️⃣ intro", "#️⃣ tag"];
+        names.humane_sort();
+        assert_eq!(names, vec!["1️⃣ intro", "2\u{20E3} middle", "🔟 outro", "1️⃣1️⃣ bonus", "#️⃣ tag"]);
+    }
+
+    #[test]
+    fn sort_by_cached_key() {
+        use HumaneSortableByKey;
+        let mut calls = 0;
+        let mut files = vec![("b", "Track 10"), ("c", "track 9"), ("a", "TRACK 1")];
+        files.humane_sort_by_cached_key(|f| {
+            calls += 1;
+            f.1.to_lowercase()
+        });
+        assert_eq!(files, vec![("a", "TRACK 1"), ("c", "track 9"), ("b", "Track 10")]);
+        assert_eq!(calls, 3);
+    }
 }
 
 fn sorting_type(x: &str) -> SortingType {
-    let num: Result<u64, _> = x.parse();
-    match num {
-        Ok(_) => SortingType::Numeric,
-        _ => SortingType::NonNumeric
+    match grapheme_digits(x) {
+        Some(_) => SortingType::Numeric,
+        None => SortingType::NonNumeric
+    }
+}
+
+/// The ASCII digits, indexed by their value.
+const DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+/// Returns the ASCII digits a single grapheme stands for, if it is numeric.
+///
+/// Besides plain digits this recognizes the decimal digits of other scripts ("٣", "３"),
+/// keycap sequences ("1️⃣", with or without the emoji variation selector) and the keycap ten
+/// emoji ("🔟").
+fn grapheme_digits(grapheme: &str) -> Option<&'static str> {
+    if grapheme.len() == 1 && grapheme.as_bytes()[0].is_ascii_digit() {
+        return Some(DIGITS[(grapheme.as_bytes()[0] - b'0') as usize])
+    }
+    if let Some(rest) = grapheme.strip_suffix('\u{20E3}') {
+        let digit = rest.strip_suffix('\u{FE0F}').unwrap_or(rest);
+        if digit.len() == 1 && digit.as_bytes()[0].is_ascii_digit() {
+            return Some(DIGITS[(digit.as_bytes()[0] - b'0') as usize])
+        }
+    }
+    if grapheme == "\u{1F51F}" {
+        return Some("10")
+    }
+    let mut chars = grapheme.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => digits::value(c).map(|value| DIGITS[value]),
+        _ => None
+    }
+}
+
+/// Returns the plain decimal digits of a numeric token.
+fn numeric_digits(token: &str) -> Cow<'_, str> {
+    if token.bytes().all(|b| b.is_ascii_digit()) {
+        Cow::Borrowed(token)
+    } else {
+        Cow::Owned(token.graphemes(true).filter_map(grapheme_digits).collect())
     }
 }
 
 /// Trait for collections of `HumaneOrder` types.
 pub trait HumaneSortable {
     fn humane_sort(&mut self);
+    /// Sorts using the comparison rules configured in `options`.
+    fn humane_sort_with(&mut self, options: &SortOptions);
+    /// Sorts without preserving the order of humane-equal elements, which does not allocate
+    /// and is usually faster.
+    ///
+    /// ```
+    /// use humanesort::HumaneSortable;
+    /// let mut names = vec!["img-10", "img-9", "img-1"];
+    /// names.humane_sort_unstable();
+    /// assert_eq!(names, vec!["img-1", "img-9", "img-10"]);
+    /// ```
+    fn humane_sort_unstable(&mut self);
+    /// Sorts in descending humane order. Unlike sorting and then reversing, humane-equal
+    /// elements keep their original order.
+    ///
+    /// ```
+    /// use humanesort::HumaneSortable;
+    /// let mut names = vec!["img-9", "img-07", "img-10", "img-7"];
+    /// names.humane_sort_desc();
+    /// assert_eq!(names, vec!["img-10", "img-9", "img-07", "img-7"]);
+    /// ```
+    fn humane_sort_desc(&mut self);
+    /// Returns whether the elements are in humane order, stopping at the first pair that is
+    /// not.
+    ///
+    /// ```
+    /// use humanesort::HumaneSortable;
+    /// assert!(["img-1", "img-9", "img-10"].is_humane_sorted());
+    /// assert!(!["img-10", "img-9"].is_humane_sorted());
+    /// ```
+    fn is_humane_sorted(&self) -> bool;
 }
 
 impl<T> HumaneSortable for [T] where T: HumaneOrder {
     fn humane_sort(&mut self) {
         self.sort_by(|a, b| a.humane_cmp(b))
     }
+
+    fn humane_sort_with(&mut self, options: &SortOptions) {
+        self.sort_by(|a, b| a.humane_cmp_with(b, options))
+    }
+
+    fn humane_sort_unstable(&mut self) {
+        self.sort_unstable_by(|a, b| a.humane_cmp(b))
+    }
+
+    fn humane_sort_desc(&mut self) {
+        self.sort_by(|a, b| b.humane_cmp(a))
+    }
+
+    fn is_humane_sorted(&self) -> bool {
+        iter::is_humane_sorted(self)
+    }
+}
+
+impl<T, const N: usize> HumaneSortable for [T; N] where T: HumaneOrder {
+    fn humane_sort(&mut self) {
+        self.as_mut_slice().humane_sort()
+    }
+
+    fn humane_sort_with(&mut self, options: &SortOptions) {
+        self.as_mut_slice().humane_sort_with(options)
+    }
+
+    fn humane_sort_unstable(&mut self) {
+        self.as_mut_slice().humane_sort_unstable()
+    }
+
+    fn humane_sort_desc(&mut self) {
+        self.as_mut_slice().humane_sort_desc()
+    }
+
+    fn is_humane_sorted(&self) -> bool {
+        self.as_slice().is_humane_sorted()
+    }
+}
+
+/// Trait for sorting slices of arbitrary types by a humane key.
+pub trait HumaneSortableByKey<T> {
+    /// Sorts by the string key returned from `f`, which is called exactly once per element.
+    ///
+    /// The key may be borrowed or owned, computed keys such as lowercased or trimmed names can
+    /// be returned as `String` or `Cow<str>` without being recomputed for every comparison.
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use humanesort::HumaneSortableByKey;
+    /// let mut names = vec![" photo-10", "photo-9", "photo-1 "];
+    /// names.humane_sort_by_cached_key(|name| Cow::Borrowed(name.trim()));
+    /// assert_eq!(names, vec!["photo-1 ", "photo-9", " photo-10"]);
+    /// ```
+    fn humane_sort_by_cached_key<K, F>(&mut self, f: F) where F: FnMut(&T) -> K, K: AsRef<str>;
+
+    /// Sorts by a key borrowed from each element, such as a name field, which is compared with
+    /// `humane_cmp`.
+    ///
+    /// The key is looked up for every comparison, which is cheap for fields. Keys that are
+    /// expensive to compute are better sorted with `humane_sort_by_cached_key`.
+    ///
+    /// ```
+    /// use humanesort::HumaneSortableByKey;
+    /// struct FileEntry { name: String, size: u64 }
+    /// let mut entries = vec![
+    ///     FileEntry { name: "log-10".to_string(), size: 3 },
+    ///     FileEntry { name: "log-9".to_string(), size: 7 },
+    /// ];
+    /// entries.humane_sort_by_key(|entry| &entry.name);
+    /// assert_eq!(entries[0].size, 7);
+    /// ```
+    fn humane_sort_by_key<K, F>(&mut self, f: F) where F: FnMut(&T) -> &K, K: HumaneOrder + ?Sized;
+}
+
+impl<T> HumaneSortableByKey<T> for [T] {
+    fn humane_sort_by_cached_key<K, F>(&mut self, f: F) where F: FnMut(&T) -> K, K: AsRef<str> {
+        let keys: Vec<K> = self.iter().map(f).collect();
+        let indices = key::KeyArena::new(&keys).sorted_indices();
+        key::apply_permutation(self, indices);
+    }
+
+    fn humane_sort_by_key<K, F>(&mut self, mut f: F) where F: FnMut(&T) -> &K, K: HumaneOrder + ?Sized {
+        self.sort_by(|a, b| f(a).humane_cmp(f(b)))
+    }
+}
+
+/// Trait for searching slices that are in humane order.
+pub trait HumaneSearchable<T> {
+    /// Binary searches for `x`, like `binary_search` but comparing with `humane_cmp`.
+    ///
+    /// Returns the index of a humane-equal element, or the index where `x` could be inserted
+    /// keeping the order if there is none. If several elements are humane-equal to `x`, any
+    /// one of them may be found.
+    ///
+    /// ```
+    /// use humanesort::HumaneSearchable;
+    /// let names = ["file-1", "file-2", "file-10"];
+    /// assert_eq!(names.humane_binary_search(&"file-02"), Ok(1));
+    /// assert_eq!(names.humane_binary_search(&"file-9"), Err(2));
+    /// ```
+    fn humane_binary_search(&self, x: &T) -> Result<usize, usize> where T: HumaneOrder;
+    /// Binary searches for the element whose key returned by `f` is humane-equal to `key`, for
+    /// slices sorted by that key.
+    ///
+    /// ```
+    /// use humanesort::HumaneSearchable;
+    /// let index = vec![(String::from("img-9"), 1), (String::from("img-10"), 2)];
+    /// assert_eq!(index.humane_binary_search_by_key("img-10", |entry| entry.0.as_str()), Ok(1));
+    /// assert_eq!(index.humane_binary_search_by_key("img-1", |entry| entry.0.as_str()), Err(0));
+    /// ```
+    fn humane_binary_search_by_key<K, F>(&self, key: &K, f: F) -> Result<usize, usize>
+        where F: FnMut(&T) -> &K, K: HumaneOrder + ?Sized;
+}
+
+impl<T> HumaneSearchable<T> for [T] {
+    fn humane_binary_search(&self, x: &T) -> Result<usize, usize> where T: HumaneOrder {
+        self.binary_search_by(|probe| probe.humane_cmp(x))
+    }
+
+    fn humane_binary_search_by_key<K, F>(&self, key: &K, mut f: F) -> Result<usize, usize>
+        where F: FnMut(&T) -> &K, K: HumaneOrder + ?Sized {
+        self.binary_search_by(|probe| f(probe).humane_cmp(key))
+    }
+}
+
+/// Compares two strings in humane order, as a plain function for `sort_by`, `max_by` and
+/// other places that take a comparator.
+///
+/// ```
+/// let mut names = vec!["track-10".to_string(), "track-9".to_string()];
+/// names.sort_by(humanesort::compare);
+/// assert_eq!(names, ["track-9", "track-10"]);
+/// assert_eq!(["v8", "v12", "v9"].iter().copied().max_by(humanesort::compare), Some("v12"));
+/// ```
+pub fn compare<S>(a: &S, b: &S) -> Ordering where S: AsRef<str> + ?Sized {
+    a.as_ref().humane_cmp(b.as_ref())
+}
+
+/// Compares two strings using the rules configured in `options`.
+///
+/// ```
+/// use humanesort::{CaseOrder, SortOptions};
+/// let options = SortOptions::new().case_order(CaseOrder::Ignore);
+/// let mut names = ["beta", "Alpha"];
+/// names.sort_by(|a, b| humanesort::compare_with(a, b, &options));
+/// assert_eq!(names, ["Alpha", "beta"]);
+/// ```
+pub fn compare_with<S>(a: &S, b: &S, options: &SortOptions) -> Ordering where S: AsRef<str> + ?Sized {
+    a.as_ref().humane_cmp_with(b.as_ref(), options)
 }
 
 /// Trait for types that can be ordered in a human friendly way.
+///
+/// It is implemented for `str`, `String`, `Box<str>`, `Cow<str>`, `Rc<str>`, `Arc<str>`,
+/// references to implementing types, paths, numbers, tuples and `Option`.
+///
+/// Earlier versions implemented it for every `T: AsRef<str>` instead. That blanket
+/// implementation ruled out implementations for paths and numbers and was removed, so other
+/// types that only implement `AsRef<str>` need an implementation delegating to `str`:
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use humanesort::HumaneOrder;
+/// struct FileName(String);
+///
+/// impl HumaneOrder for FileName {
+///     fn humane_cmp(&self, other: &Self) -> Ordering {
+///         self.0.humane_cmp(&other.0)
+///     }
+/// }
+///
+/// assert_eq!(FileName("track-9".into()).humane_cmp(&FileName("track-10".into())), Ordering::Less);
+/// ```
 pub trait HumaneOrder {
     fn humane_cmp(&self, other: &Self) -> Ordering;
+
+    /// Compares using the rules configured in `options`.
+    ///
+    /// Types without configurable behavior can rely on the default, which ignores `options`.
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        let _ = options;
+        self.humane_cmp(other)
+    }
 }
 
-impl<T> HumaneOrder for T where T: AsRef<str> {
+impl HumaneOrder for str {
     fn humane_cmp(&self, other: &Self) -> Ordering {
-        let sorting_type_function = &sorting_type;
-        let mut self_tokens = TokenIterator::new(self.as_ref(), sorting_type_function);
-        let mut other_tokens = TokenIterator::new(other.as_ref(), sorting_type_function);
-        loop {
-            match (self_tokens.next(), other_tokens.next()) {
-                (None, None) => return Ordering::Equal,
-                (None, _) => return Ordering::Less,
-                (_, None) => return Ordering::Greater,
-                (Some(ours), Some(theirs)) => {
-                    match (ours.1, theirs.1) {
-                        (SortingType::Numeric, SortingType::NonNumeric) => return Ordering::Less,
-                        (SortingType::NonNumeric, SortingType::Numeric) => return Ordering::Greater,
-                        (SortingType::Numeric, SortingType::Numeric) => {
-                            let cmp = ours.0.parse::<usize>().unwrap().cmp(&theirs.0.parse::<usize>().unwrap());
-                            if cmp != Ordering::Equal {
-                                return cmp
-                            }
-                        }
-                        (SortingType::NonNumeric, SortingType::NonNumeric) => {
-                            let cmp = ours.0.cmp(theirs.0);
-                            if cmp != Ordering::Equal {
-                                return cmp
-                            }
-                        }
-                    }
+        let start = equal_prefix(self, other);
+        let self_tokens = TokenIterator::digit_runs(&self[start..]);
+        let other_tokens = TokenIterator::digit_runs(&other[start..]);
+        compare_token_streams(self_tokens, other_tokens)
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        if *options == SortOptions::default() {
+            return self.humane_cmp(other)
+        }
+        if options.empty_order == EmptyOrder::Last && self.is_empty() != other.is_empty() {
+            return self.is_empty().cmp(&other.is_empty())
+        }
+        if options.copy_markers {
+            let plain = SortOptions { copy_markers: false, ..options.clone() };
+            let (ours, our_copy) = copies::strip_copy_marker(self);
+            let (theirs, their_copy) = copies::strip_copy_marker(other);
+            return ours.humane_cmp_with(&theirs, &plain)
+                .then(our_copy.cmp(&their_copy))
+                .then_with(|| self.humane_cmp_with(other, &plain))
+        }
+        if options.extension_order != ExtensionOrder::Inline {
+            let plain = SortOptions { extension_order: ExtensionOrder::Inline, ..options.clone() };
+            let (our_stem, our_extension) = copies::split_extension(self);
+            let (their_stem, their_extension) = copies::split_extension(other);
+            let stems = || our_stem.humane_cmp_with(their_stem, &plain);
+            let extensions = || our_extension.trim_start_matches('.').humane_cmp_with(their_extension.trim_start_matches('.'), &plain);
+            let cmp = match options.extension_order {
+                ExtensionOrder::First => extensions().then_with(stems),
+                _ => stems().then_with(extensions)
+            };
+            return cmp.then_with(|| self.humane_cmp_with(other, &plain))
+        }
+        let limits = options.limits;
+        let (ours, theirs) = (limits.truncate(self), limits.truncate(other));
+        let max_tokens = limits.max_tokens.unwrap_or(usize::MAX);
+        let self_tokens = token::Tokens::new(ours, options).take(max_tokens);
+        let other_tokens = token::Tokens::new(theirs, options).take(max_tokens);
+        let cmp = compare_token_streams_by(self_tokens, other_tokens, |a, b| token::compare(&a, &b, options));
+        let cmp = match (cmp, options.zero_order) {
+            (Ordering::Equal, ZeroOrder::Ignore) => Ordering::Equal,
+            (Ordering::Equal, zero_order) => token::compare_zeros(token::Tokens::new(ours, options).take(max_tokens),
+                                                                  token::Tokens::new(theirs, options).take(max_tokens), zero_order),
+            _ => cmp
+        };
+        let cmp = match (cmp, options.case_order) {
+            (Ordering::Equal, CaseOrder::CodePoint) | (Ordering::Equal, CaseOrder::Ignore) => Ordering::Equal,
+            (Ordering::Equal, case_order) => options::compare_case(ours, theirs, case_order),
+            _ => cmp
+        };
+        // Normalized text can be equal for distinct strings, which are then compared as written.
+        let cmp = match (cmp, options.normalizes_text()) {
+            (Ordering::Equal, true) => {
+                let plain = SortOptions { ignore_diacritics: false, ignore_punctuation: false, whitespace: Whitespace::Keep, ..options.clone() };
+                self.humane_cmp_with(other, &plain)
+            }
+            _ => cmp
+        };
+        // Limited comparisons ignore parts of the strings, the final tiebreak keeps the order
+        // total.
+        match (cmp, limits == Limits::default()) {
+            (Ordering::Equal, false) => self.cmp(other),
+            _ => cmp
+        }
+    }
+}
+
+/// Returns the length of the common prefix of two ASCII strings that comparison can skip.
+///
+/// A number the prefix ends in is left out, so it is compared with the digits that follow.
+/// Tokens of non-ASCII strings depend on their graphemes, for them nothing is skipped.
+fn equal_prefix(ours: &str, theirs: &str) -> usize {
+    if !ours.is_ascii() || !theirs.is_ascii() {
+        return 0
+    }
+    let prefix = scan::common_prefix(ours.as_bytes(), theirs.as_bytes());
+    prefix - ours.as_bytes()[..prefix].iter().rev().take_while(|b| b.is_ascii_digit()).count()
+}
+
+/// Compares two tokenized strings section by section.
+fn compare_token_streams<'a, 'b, I, J>(ours: I, theirs: J) -> Ordering
+    where I: Iterator<Item = (&'a str, SortingType)>, J: Iterator<Item = (&'b str, SortingType)> {
+    compare_token_streams_by(ours, theirs, compare_tokens)
+}
+
+fn compare_token_streams_by<I, J, F>(mut ours: I, mut theirs: J, mut compare: F) -> Ordering
+    where I: Iterator, J: Iterator, F: FnMut(I::Item, J::Item) -> Ordering {
+    loop {
+        match (ours.next(), theirs.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, _) => return Ordering::Less,
+            (_, None) => return Ordering::Greater,
+            (Some(a), Some(b)) => {
+                let cmp = compare(a, b);
+                if cmp != Ordering::Equal {
+                    return cmp
                 }
             }
         }
     }
 }
 
+fn compare_tokens(ours: (&str, SortingType), theirs: (&str, SortingType)) -> Ordering {
+    match (ours.1, theirs.1) {
+        (SortingType::Numeric, SortingType::NonNumeric) => Ordering::Less,
+        (SortingType::NonNumeric, SortingType::Numeric) => Ordering::Greater,
+        (SortingType::Numeric, SortingType::Numeric) => compare_numbers(&numeric_digits(ours.0), &numeric_digits(theirs.0)),
+        (SortingType::NonNumeric, SortingType::NonNumeric) => ours.0.cmp(theirs.0)
+    }
+}
+
+/// Compares two runs of ASCII digits by their value.
+///
+/// Runs of any length are supported: without leading zeros the longer run is the larger
+/// number, runs of the same length compare like their digits.
+fn compare_numbers(ours: &str, theirs: &str) -> Ordering {
+    let ours = ours.trim_start_matches('0');
+    let theirs = theirs.trim_start_matches('0');
+    ours.len().cmp(&theirs.len()).then_with(|| ours.cmp(theirs))
+}
+
+impl<T> HumaneOrder for &T where T: HumaneOrder + ?Sized {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        (**self).humane_cmp(*other)
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        (**self).humane_cmp_with(*other, options)
+    }
+}
+
+impl<T> HumaneOrder for &mut T where T: HumaneOrder + ?Sized {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        (**self).humane_cmp(*other)
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        (**self).humane_cmp_with(*other, options)
+    }
+}
+
+macro_rules! impl_humane_order_for_str {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> HumaneOrder for $t {
+                fn humane_cmp(&self, other: &Self) -> Ordering {
+                    let (ours, theirs): (&str, &str) = (self.as_ref(), other.as_ref());
+                    ours.humane_cmp(theirs)
+                }
+
+                fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+                    let (ours, theirs): (&str, &str) = (self.as_ref(), other.as_ref());
+                    ours.humane_cmp_with(theirs, options)
+                }
+            }
+        )*
+    }
+}
+
+impl_humane_order_for_str!(String, Box<str>, Cow<'a, str>, Rc<str>, Arc<str>);
+
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 enum SortingType {
     Numeric,
     NonNumeric
 }
 
+#[derive(Clone)]
 struct TokenIterator<'a, T> where T: Eq + Copy + 'a {
-    token_type: &'a Fn(&str) -> T,
+    token_type: &'a dyn Fn(&str) -> T,
     string: &'a str,
-    grapheme_iterator: Peekable<GraphemeIndices<'a>>
+    /// The graphemes of the string, `None` for ASCII strings, which are split into bytes.
+    ///
+    /// The bytes of ASCII strings are its graphemes except for "\r\n", token types are expected
+    /// to classify "\r\n" like "\r" and "\n".
+    grapheme_iterator: Option<Peekable<GraphemeIndices<'a>>>,
+    /// Whether the token type is `sorting_type`, which splits ASCII strings between digits and
+    /// other bytes, so runs can be found with `scan::run_len`.
+    digit_runs: bool,
+    /// The start of the next token of an ASCII string.
+    position: usize
 }
 
 impl<'a, T> TokenIterator<'a, T> where T: Eq + Copy {
-    fn new(s: &'a str, func: &'a Fn(&str) -> T) -> Self {
+    fn new(s: &'a str, func: &'a dyn Fn(&str) -> T) -> Self {
+        match s.is_ascii() {
+            true => TokenIterator { token_type: func, string: s, grapheme_iterator: None, digit_runs: false, position: 0 },
+            false => TokenIterator::with_graphemes(s, func)
+        }
+    }
+
+    /// Creates an iterator that segments `s` into graphemes, also if it is ASCII.
+    fn with_graphemes(s: &'a str, func: &'a dyn Fn(&str) -> T) -> Self {
         TokenIterator {
             token_type: func,
             string: s,
-            grapheme_iterator: UnicodeSegmentation::grapheme_indices(&s[..], true).peekable()
+            grapheme_iterator: Some(UnicodeSegmentation::grapheme_indices(s, true).peekable()),
+            digit_runs: false,
+            position: 0
+        }
+    }
+
+    /// Returns the next run of bytes of the same type of an ASCII string.
+    fn next_ascii(&mut self) -> Option<(&'a str, T)> {
+        let (s, start) = (self.string, self.position);
+        if start >= s.len() {
+            return None
         }
+        let current_type = (self.token_type)(&s[start..start + 1]);
+        let len = 1 + match self.digit_runs {
+            true => scan::run_len(&s.as_bytes()[start + 1..], s.as_bytes()[start].is_ascii_digit()),
+            false => (start + 1..s.len()).take_while(|&i| (self.token_type)(&s[i..i + 1]) == current_type).count()
+        };
+        self.position = start + len;
+        Some((&s[start..start + len], current_type))
+    }
+}
+
+impl<'a> TokenIterator<'a, SortingType> {
+    /// Creates an iterator that splits `s` into numbers and non-numbers with `sorting_type`.
+    fn digit_runs(s: &'a str) -> Self {
+        let mut iterator = TokenIterator::new(s, &sorting_type);
+        iterator.digit_runs = iterator.grapheme_iterator.is_none();
+        iterator
     }
 }
 
@@ -166,20 +874,24 @@ impl<'a, T> Iterator for TokenIterator<'a, T> where T: Eq + Copy {
     type Item = (&'a str, T);
 
     fn next(&mut self) -> Option<(&'a str, T)> {
-        let (first_index, mut grapheme) = match self.grapheme_iterator.next() {
+        let graphemes = match self.grapheme_iterator {
+            Some(ref mut graphemes) => graphemes,
+            None => return self.next_ascii()
+        };
+        let (first_index, mut grapheme) = match graphemes.next() {
             Some((i, s)) => (i, s),
             None => return None // This is only reached when the first element is None
         };
         loop {
             let current_type = (self.token_type)(grapheme);
-            let (next_index, next_grapheme) = match self.grapheme_iterator.peek() {
+            let (next_index, next_grapheme) = match graphemes.peek() {
                 Some(&(i, g)) => (i, g),
                 None => return Some((&self.string[first_index..self.string.len()], (self.token_type)(grapheme)))
             };
             if current_type != (self.token_type)(next_grapheme) {
                 return Some((&self.string[first_index..next_index], current_type))
             }
-            let tup = match self.grapheme_iterator.next() {
+            let tup = match graphemes.next() {
                 Some((i, s)) => (i, s),
                 None => return None // This is only reached when the first element is None
             };
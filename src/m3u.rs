@@ -0,0 +1,149 @@
+//! Sorting M3U and M3U8 playlists.
+//!
+//! Every entry keeps the directives preceding its location (such as `#EXTINF`), so metadata
+//! stays paired with the right file when entries are reordered.
+use std::cmp::Ordering;
+use std::fmt;
+use std::string::{String, ToString};
+use std::vec::Vec;
+use ::HumaneOrder;
+
+/// A single playlist entry.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Entry {
+    /// Directive, comment, and blank lines preceding the location, in their original order.
+    pub directives: Vec<String>,
+    /// The file path or URL of the entry.
+    pub location: String
+}
+
+impl Entry {
+    /// Returns the title given in the entry's `#EXTINF` directive, if any.
+    pub fn title(&self) -> Option<&str> {
+        self.directives.iter()
+            .filter_map(|line| line.strip_prefix("#EXTINF:"))
+            .filter_map(|info| info.split_once(',').map(|(_, title)| title.trim()))
+            .find(|title| !title.is_empty())
+    }
+
+    /// Returns the last path segment of the location.
+    pub fn file_name(&self) -> &str {
+        let location = self.location.trim_end_matches(['/', '\\']);
+        match location.rfind(['/', '\\']) {
+            Some(i) => &location[i + 1..],
+            None => location
+        }
+    }
+}
+
+/// What to order playlist entries by.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SortBy {
+    /// The `#EXTINF` title, falling back to the file name for entries without one.
+    Title,
+    /// The last path segment of the location.
+    FileName,
+    /// The full location.
+    Location
+}
+
+/// A parsed M3U playlist.
+///
+/// ```
+/// use humanesort::m3u::{Playlist, SortBy};
+/// let mut playlist = Playlist::parse("#EXTM3U\n#EXTINF:60,Track 10\nb.mp3\n#EXTINF:60,Track 9\na.mp3\n");
+/// playlist.sort(SortBy::Title);
+/// assert_eq!(playlist.to_string(), "#EXTM3U\n#EXTINF:60,Track 9\na.mp3\n#EXTINF:60,Track 10\nb.mp3\n");
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Playlist {
+    /// The `#EXTM3U` header line, if present.
+    pub header: Option<String>,
+    /// The entries in playlist order.
+    pub entries: Vec<Entry>,
+    /// Lines following the last entry.
+    pub trailer: Vec<String>
+}
+
+impl Playlist {
+    /// Parses a playlist, accepting both `\n` and `\r\n` line endings.
+    pub fn parse(input: &str) -> Playlist {
+        let mut playlist = Playlist::default();
+        let mut lines = input.lines().peekable();
+        if let Some(first) = lines.peek() {
+            if first.trim_start_matches('\u{feff}').starts_with("#EXTM3U") {
+                playlist.header = lines.next().map(String::from);
+            }
+        }
+        let mut directives = Vec::new();
+        for line in lines {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                directives.push(line.to_string());
+            } else {
+                playlist.entries.push(Entry {
+                    directives: directives.split_off(0),
+                    location: line.to_string()
+                });
+            }
+        }
+        playlist.trailer = directives;
+        playlist
+    }
+
+    /// Sorts the entries humanely by the given property, keeping the order of equal entries.
+    pub fn sort(&mut self, by: SortBy) {
+        self.entries.sort_by(|a, b| compare_entries(a, b, by));
+    }
+}
+
+fn compare_entries(a: &Entry, b: &Entry, by: SortBy) -> Ordering {
+    match by {
+        SortBy::Title => {
+            let a_title = a.title().unwrap_or_else(|| a.file_name());
+            let b_title = b.title().unwrap_or_else(|| b.file_name());
+            a_title.humane_cmp(b_title)
+        }
+        SortBy::FileName => a.file_name().humane_cmp(b.file_name()),
+        SortBy::Location => a.location.humane_cmp(&b.location)
+    }
+}
+
+impl fmt::Display for Playlist {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref header) = self.header {
+            writeln!(f, "{}", header)?;
+        }
+        for entry in &self.entries {
+            for directive in &entry.directives {
+                writeln!(f, "{}", directive)?;
+            }
+            writeln!(f, "{}", entry.location)?;
+        }
+        for line in &self.trailer {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+    use std::vec::Vec;
+    use super::{Playlist, SortBy};
+
+    #[test]
+    fn sort_by_file_name_keeps_metadata() {
+        let input = "#EXTM3U\r\n#EXTINF:10,Zeta\r\nmusic/track-10.mp3\r\n\r\n#EXTINF:20,Alpha\r\nhttp://host/x/track-2.mp3\r\nC:\\music\\track-1.mp3\r\n# end\r\n";
+        let mut playlist = Playlist::parse(input);
+        assert_eq!(playlist.entries.len(), 3);
+        assert_eq!(playlist.entries[0].title(), Some("Zeta"));
+        assert_eq!(playlist.entries[2].file_name(), "track-1.mp3");
+        playlist.sort(SortBy::FileName);
+        assert_eq!(playlist.to_string(), "#EXTM3U\nC:\\music\\track-1.mp3\n\n#EXTINF:20,Alpha\nhttp://host/x/track-2.mp3\n#EXTINF:10,Zeta\nmusic/track-10.mp3\n# end\n");
+        playlist.sort(SortBy::Title);
+        let titles: Vec<_> = playlist.entries.iter().map(|e| e.title().unwrap_or("-")).collect();
+        assert_eq!(titles, vec!["Alpha", "Zeta", "-"]);
+    }
+}
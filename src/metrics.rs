@@ -0,0 +1,79 @@
+//! Instrumented sorting, counting the work done by the comparator.
+//!
+//! These functions behave exactly like their uninstrumented counterparts but additionally
+//! return a `Metrics` value, so performance regressions in downstream code can be attributed
+//! to e.g. a growing number of comparisons or unusually long token sequences.
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::ops::AddAssign;
+use ::{compare_token_streams_by, compare_tokens, SortingType, TokenIterator};
+
+/// Counters collected during instrumented comparisons.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy)]
+pub struct Metrics {
+    /// Number of string comparisons performed.
+    pub comparisons: u64,
+    /// Number of tokens produced by the tokenizer.
+    pub tokens: u64,
+    /// Number of numeric tokens parsed into values.
+    pub numeric_parses: u64
+}
+
+impl AddAssign for Metrics {
+    fn add_assign(&mut self, other: Metrics) {
+        self.comparisons += other.comparisons;
+        self.tokens += other.tokens;
+        self.numeric_parses += other.numeric_parses;
+    }
+}
+
+/// Compares `a` and `b` like `humane_cmp`, adding the work done to `metrics`.
+pub fn humane_cmp_instrumented(a: &str, b: &str, metrics: &mut Metrics) -> Ordering {
+    let tokens = Cell::new(0);
+    let mut numeric_parses = 0;
+    let cmp = {
+        let ours = TokenIterator::digit_runs(a).inspect(|_| tokens.set(tokens.get() + 1));
+        let theirs = TokenIterator::digit_runs(b).inspect(|_| tokens.set(tokens.get() + 1));
+        compare_token_streams_by(ours, theirs, |x, y| {
+            if x.1 == SortingType::Numeric && y.1 == SortingType::Numeric {
+                numeric_parses += 2;
+            }
+            compare_tokens(x, y)
+        })
+    };
+    metrics.comparisons += 1;
+    metrics.tokens += tokens.get();
+    metrics.numeric_parses += numeric_parses;
+    cmp
+}
+
+/// Sorts `items` in humane order and reports the work done.
+///
+/// ```
+/// use humanesort::metrics::humane_sort_instrumented;
+/// let mut names = vec!["file-10", "file-2", "file-1"];
+/// let metrics = humane_sort_instrumented(&mut names);
+/// assert_eq!(names, vec!["file-1", "file-2", "file-10"]);
+/// assert!(metrics.comparisons >= 2);
+/// assert!(metrics.numeric_parses > 0);
+/// ```
+pub fn humane_sort_instrumented<S>(items: &mut [S]) -> Metrics where S: AsRef<str> {
+    let mut metrics = Metrics::default();
+    items.sort_by(|a, b| humane_cmp_instrumented(a.as_ref(), b.as_ref(), &mut metrics));
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use super::{humane_cmp_instrumented, Metrics};
+
+    #[test]
+    fn counts_tokens_and_parses() {
+        let mut metrics = Metrics::default();
+        assert_eq!(humane_cmp_instrumented("a1b", "a2", &mut metrics), Ordering::Less);
+        assert_eq!(metrics, Metrics { comparisons: 1, tokens: 4, numeric_parses: 2 });
+        metrics += Metrics { comparisons: 1, tokens: 0, numeric_parses: 0 };
+        assert_eq!(metrics.comparisons, 2);
+    }
+}
@@ -0,0 +1,116 @@
+//! Sorting by several keys, each with its own direction.
+use std::boxed::Box;
+use std::cmp::Ordering;
+use std::vec::Vec;
+use ::HumaneOrder;
+
+/// Direction of a single sort key.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Direction {
+    /// Smallest first.
+    Ascending,
+    /// Largest first.
+    Descending
+}
+
+impl Direction {
+    /// Applies the direction to an ascending `ordering`.
+    pub fn apply(self, ordering: Ordering) -> Ordering {
+        match self {
+            Direction::Ascending => ordering,
+            Direction::Descending => ordering.reverse()
+        }
+    }
+}
+
+/// Builder for comparing values by a sequence of keys.
+///
+/// Keys are compared in the order they were added, later keys only break ties of earlier ones.
+///
+/// ```
+/// use humanesort::multikey::{Direction, MultiKeySort};
+/// struct Track { album: String, number: u32 }
+/// let mut tracks = vec![
+///     Track { album: "Vol 10".into(), number: 1 },
+///     Track { album: "Vol 2".into(), number: 1 },
+///     Track { album: "Vol 2".into(), number: 2 },
+/// ];
+/// MultiKeySort::new()
+///     .then_by(|t: &Track| t.album.as_str(), Direction::Ascending)
+///     .then_by_ord(|t: &Track| t.number, Direction::Descending)
+///     .sort(&mut tracks);
+/// let order: Vec<_> = tracks.iter().map(|t| (t.album.as_str(), t.number)).collect();
+/// assert_eq!(order, vec![("Vol 2", 2), ("Vol 2", 1), ("Vol 10", 1)]);
+/// ```
+pub struct MultiKeySort<'a, T> {
+    keys: Vec<KeyComparator<'a, T>>
+}
+
+type KeyComparator<'a, T> = Box<dyn Fn(&T, &T) -> Ordering + 'a>;
+
+impl<'a, T> Default for MultiKeySort<'a, T> {
+    fn default() -> Self {
+        MultiKeySort { keys: Vec::new() }
+    }
+}
+
+impl<'a, T> MultiKeySort<'a, T> {
+    /// Creates a builder without any keys, which considers all values equal.
+    pub fn new() -> Self {
+        MultiKeySort::default()
+    }
+
+    /// Adds a string key borrowed from the value, compared humanely.
+    pub fn then_by<F>(self, key: F, direction: Direction) -> Self where F: Fn(&T) -> &str + 'a {
+        self.then_by_comparator(move |a, b| direction.apply(key(a).humane_cmp(key(b))))
+    }
+
+    /// Adds a computed key of any `HumaneOrder` type.
+    pub fn then_by_key<F, K>(self, key: F, direction: Direction) -> Self
+        where F: Fn(&T) -> K + 'a, K: HumaneOrder {
+        self.then_by_comparator(move |a, b| direction.apply(key(a).humane_cmp(&key(b))))
+    }
+
+    /// Adds a computed key compared with its `Ord` implementation, such as a track number.
+    pub fn then_by_ord<F, K>(self, key: F, direction: Direction) -> Self
+        where F: Fn(&T) -> K + 'a, K: Ord {
+        self.then_by_comparator(move |a, b| direction.apply(key(a).cmp(&key(b))))
+    }
+
+    fn then_by_comparator<F>(mut self, compare: F) -> Self where F: Fn(&T, &T) -> Ordering + 'a {
+        self.keys.push(Box::new(compare));
+        self
+    }
+
+    /// Compares two values by all keys.
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        for key in &self.keys {
+            let cmp = key(a, b);
+            if cmp != Ordering::Equal {
+                return cmp
+            }
+        }
+        Ordering::Equal
+    }
+
+    /// Sorts `items` by all keys, keeping the order of values that are equal on all keys.
+    pub fn sort(&self, items: &mut [T]) {
+        items.sort_by(|a, b| self.compare(a, b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+    use super::{Direction, MultiKeySort};
+
+    #[test]
+    fn mixed_directions() {
+        let mut rows = vec![("a2", "x1"), ("a10", "x2"), ("a2", "x10"), ("a10", "x1")];
+        MultiKeySort::new()
+            .then_by(|r: &(&str, &str)| r.0, Direction::Descending)
+            .then_by_key(|r: &(&str, &str)| r.1.to_string(), Direction::Ascending)
+            .sort(&mut rows);
+        assert_eq!(rows, vec![("a10", "x1"), ("a10", "x2"), ("a2", "x1"), ("a2", "x10")]);
+    }
+}
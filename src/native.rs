@@ -0,0 +1,114 @@
+//! Text comparison delegated to the collator of the operating system.
+//!
+//! Windows uses `CompareStringEx` with the user's default locale, macOS uses
+//! `CFStringCompareWithOptionsAndLocale` with the current locale. Other platforms have no
+//! native collator and report `None`.
+use std::cmp::Ordering;
+
+/// Compares two text tokens with the platform collator.
+#[cfg(windows)]
+pub(crate) fn compare(ours: &str, theirs: &str) -> Option<Ordering> {
+    use std::os::raw::c_void;
+    use std::ptr;
+
+    const CSTR_LESS_THAN: i32 = 1;
+    const CSTR_EQUAL: i32 = 2;
+    const CSTR_GREATER_THAN: i32 = 3;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CompareStringEx(locale: *const u16, flags: u32, string1: *const u16, count1: i32,
+                           string2: *const u16, count2: i32, version: *mut c_void,
+                           reserved: *mut c_void, param: isize) -> i32;
+    }
+
+    let ours: Vec<u16> = ours.encode_utf16().collect();
+    let theirs: Vec<u16> = theirs.encode_utf16().collect();
+    if ours.len() > i32::MAX as usize || theirs.len() > i32::MAX as usize {
+        return None
+    }
+    // A null locale name selects the user's default locale.
+    let result = unsafe {
+        CompareStringEx(ptr::null(), 0, ours.as_ptr(), ours.len() as i32, theirs.as_ptr(),
+                        theirs.len() as i32, ptr::null_mut(), ptr::null_mut(), 0)
+    };
+    match result {
+        CSTR_LESS_THAN => Some(Ordering::Less),
+        CSTR_EQUAL => Some(Ordering::Equal),
+        CSTR_GREATER_THAN => Some(Ordering::Greater),
+        _ => None
+    }
+}
+
+/// Compares two text tokens with the platform collator.
+#[cfg(target_os = "macos")]
+pub(crate) fn compare(ours: &str, theirs: &str) -> Option<Ordering> {
+    use std::os::raw::c_void;
+    use std::ptr;
+
+    type CFIndex = isize;
+    type CFStringRef = *const c_void;
+    type CFLocaleRef = *const c_void;
+
+    #[repr(C)]
+    struct CFRange {
+        location: CFIndex,
+        length: CFIndex
+    }
+
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    const K_CF_COMPARE_LOCALIZED: usize = 32;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithBytes(allocator: *const c_void, bytes: *const u8, length: CFIndex,
+                                   encoding: u32, external: u8) -> CFStringRef;
+        fn CFStringGetLength(string: CFStringRef) -> CFIndex;
+        fn CFLocaleCopyCurrent() -> CFLocaleRef;
+        fn CFStringCompareWithOptionsAndLocale(string1: CFStringRef, string2: CFStringRef,
+                                               range: CFRange, options: usize,
+                                               locale: CFLocaleRef) -> CFIndex;
+        fn CFRelease(object: *const c_void);
+    }
+
+    unsafe fn create(s: &str) -> CFStringRef {
+        CFStringCreateWithBytes(ptr::null(), s.as_ptr(), s.len() as CFIndex, K_CF_STRING_ENCODING_UTF8, 0)
+    }
+
+    unsafe {
+        let ours = create(ours);
+        let theirs = create(theirs);
+        let locale = CFLocaleCopyCurrent();
+        let result = if ours.is_null() || theirs.is_null() || locale.is_null() {
+            None
+        } else {
+            let range = CFRange { location: 0, length: CFStringGetLength(ours) };
+            Some(CFStringCompareWithOptionsAndLocale(ours, theirs, range, K_CF_COMPARE_LOCALIZED, locale).cmp(&0))
+        };
+        for object in [ours, theirs, locale] {
+            if !object.is_null() {
+                CFRelease(object);
+            }
+        }
+        result
+    }
+}
+
+/// Compares two text tokens with the platform collator.
+#[cfg(not(any(windows, target_os = "macos")))]
+pub(crate) fn compare(_ours: &str, _theirs: &str) -> Option<Ordering> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use ::{HumaneOrder, SortOptions};
+
+    #[test]
+    fn numbers_stay_numeric() {
+        let options = SortOptions::new().native_collation(true);
+        assert_eq!("file 2".humane_cmp_with("file 10", &options), Ordering::Less);
+        assert_eq!("b 1".humane_cmp_with("a 2", &options), Ordering::Greater);
+    }
+}
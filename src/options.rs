@@ -0,0 +1,743 @@
+//! Configurable comparison behavior.
+use std::cmp::Ordering;
+#[cfg(feature = "icu")]
+use collation::Collation;
+
+/// Precedence of upper and lower case letters.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum CaseOrder {
+    /// Text is compared by code point, which sorts all uppercase ASCII letters before all
+    /// lowercase ones ("B" before "a"). This is the POSIX locale behavior and the default.
+    #[default]
+    CodePoint,
+    /// Letters are compared alphabetically, strings that are otherwise equal place uppercase
+    /// first ("Apple" before "apple" before "Banana").
+    UpperFirst,
+    /// Letters are compared alphabetically, strings that are otherwise equal place lowercase
+    /// first ("apple" before "Apple" before "Banana").
+    LowerFirst,
+    /// Letters are compared alphabetically and strings that only differ in case are equal,
+    /// sorting keeps their original order.
+    Ignore
+}
+
+/// Placement of NaN values when comparing floating point numbers.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum NanOrder {
+    /// NaNs are placed according to their sign bit, negative NaNs before all numbers and
+    /// positive NaNs after them, as done by `f64::total_cmp`. This is the default.
+    #[default]
+    Sign,
+    /// All NaNs are placed before all numbers.
+    First,
+    /// All NaNs are placed after all numbers.
+    Last
+}
+
+/// Placement of numbers relative to text at the same position.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum NumberOrder {
+    /// Numbers sort before text ("2 b" before "a"). This is the default.
+    #[default]
+    First,
+    /// Numbers sort after text ("a" before "2 b").
+    Last
+}
+
+/// Which minus signs directly before a number make it negative.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum NegativeNumbers {
+    /// A minus is always a separator, "temp_-5" contains the number 5. This is the default.
+    #[default]
+    Never,
+    /// A minus is a sign if it does not follow a letter or digit, so "temp_-5" and "-5" are
+    /// negative while "track-5" and "2024-05" are not.
+    Delimited,
+    /// Every minus directly before a number is a sign, also in "track-5".
+    Always
+}
+
+/// Which runs of hexadecimal digits are numbers.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum HexNumbers {
+    /// Hexadecimal digits are digits and letters as usual. This is the default.
+    #[default]
+    Never,
+    /// Digits after a "0x" or "0X" prefix are a hexadecimal number, as in "dump_0x0A".
+    Prefixed,
+    /// Prefixed numbers and runs of hexadecimal digits starting with a digit and not adjacent to
+    /// other letters or digits, as in "dump_0A" or "7ffe". Runs of decimal digits that meet
+    /// these rules are read as hexadecimal as well.
+    Bare
+}
+
+/// Placement of empty strings, and of `None` when comparing `Option`s.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum EmptyOrder {
+    /// Empty strings sort before all other strings. This is the default.
+    #[default]
+    First,
+    /// Empty strings sort after all other strings, as placeholders at the end of a list.
+    Last
+}
+
+/// Role of file extensions, the text after the final dot of a name.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum ExtensionOrder {
+    /// Extensions are compared where they occur, like any other text. This is the default.
+    #[default]
+    Inline,
+    /// Names are compared by extension first and then by the rest of the name, which groups
+    /// files by type ("a.txt", "b.txt", "a.zip").
+    First,
+    /// Names are compared without their extension first and the extension only breaks ties
+    /// ("report.txt" and "report.pdf" before "report 2.pdf").
+    Last
+}
+
+/// Treatment of whitespace in text.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Whitespace {
+    /// Whitespace is compared like any other character. This is the default.
+    #[default]
+    Keep,
+    /// Runs of whitespace compare like a single space, so "file  2" and "file\t2" are equal to
+    /// "file 2".
+    Collapse,
+    /// Whitespace is skipped, so "file 2" equals "file2". Whitespace still separates numbers,
+    /// "1 000" contains the numbers 1 and 0.
+    Ignore
+}
+
+/// Order of numbers that only differ in leading zeros, such as "007" and "7".
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum ZeroOrder {
+    /// Such numbers are equal, sorting keeps their original order. This is the default.
+    #[default]
+    Ignore,
+    /// Fewer leading zeros first ("file7" before "file007").
+    FewerFirst,
+    /// More leading zeros first ("file007" before "file7"), as done by GNU `sort -V`.
+    MoreFirst
+}
+
+/// Options controlling the comparison rules.
+///
+/// The default options give the same results as `humane_cmp`.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use humanesort::{CaseOrder, HumaneOrder, SortOptions};
+/// let options = SortOptions::new().case_order(CaseOrder::UpperFirst);
+/// assert_eq!("Zebra 2".humane_cmp_with("apple 10", &options), Ordering::Greater);
+/// assert_eq!("File 2".humane_cmp_with("file 2", &options), Ordering::Less);
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct SortOptions {
+    pub(crate) case_order: CaseOrder,
+    pub(crate) copy_markers: bool,
+    pub(crate) dates: bool,
+    pub(crate) decimal_separator: Option<char>,
+    pub(crate) decimals: bool,
+    pub(crate) delimited_digits: bool,
+    pub(crate) durations: bool,
+    pub(crate) empty_order: EmptyOrder,
+    pub(crate) extension_order: ExtensionOrder,
+    pub(crate) hex_numbers: HexNumbers,
+    pub(crate) ignore_diacritics: bool,
+    pub(crate) ignore_punctuation: bool,
+    pub(crate) ip_addresses: bool,
+    pub(crate) limits: Limits,
+    pub(crate) nan_order: NanOrder,
+    pub(crate) negative_numbers: NegativeNumbers,
+    pub(crate) number_order: NumberOrder,
+    pub(crate) outlines: bool,
+    pub(crate) ranges: bool,
+    pub(crate) scientific: bool,
+    pub(crate) sizes: bool,
+    pub(crate) thousands_separator: Option<char>,
+    pub(crate) timestamps: bool,
+    pub(crate) whitespace: Whitespace,
+    pub(crate) zero_order: ZeroOrder,
+    #[cfg(feature = "icu")]
+    pub(crate) collation: Option<Collation>,
+    #[cfg(feature = "native-collation")]
+    pub(crate) native_collation: bool,
+    #[cfg(feature = "any_ascii")]
+    pub(crate) transliterate: bool
+}
+
+impl SortOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        SortOptions::default()
+    }
+
+    /// Sets the precedence of upper and lower case letters.
+    pub fn case_order(mut self, case_order: CaseOrder) -> Self {
+        self.case_order = case_order;
+        self
+    }
+
+    /// Sorts copies of a file directly after the original, recognizing the markers that
+    /// browsers (" (1)"), Windows (" - Copy", " - Copy (2)") and macOS (" copy", " copy 2") add
+    /// before the extension.
+    ///
+    /// Names are compared without their marker first, then copies are ordered by number with the
+    /// original first.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut names = ["report (2).pdf", "reports.pdf", "report - Copy.pdf", "report.pdf"];
+    /// names.humane_sort_with(&SortOptions::new().copy_markers(true));
+    /// assert_eq!(names, ["report.pdf", "report - Copy.pdf", "report (2).pdf", "reports.pdf"]);
+    /// ```
+    pub fn copy_markers(mut self, copy_markers: bool) -> Self {
+        self.copy_markers = copy_markers;
+        self
+    }
+
+    /// Compares dates as points in time instead of as separate numbers, so
+    /// "report-2-Jan-2024" sorts after "report-15-Dec-2023".
+    ///
+    /// Recognized are ISO dates ("2024-01-02"), optionally with a local time ("2024-01-02T10:00",
+    /// "2024-01-02 10:00:30"), dates with dots ("2.1.2024", "02.01.2024") and dates with an
+    /// English month name ("2-Jan-2024", "2 January 2024"). Timestamps with a UTC offset are
+    /// only normalized with `timestamps`.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut reports = ["report-2-Jan-2024", "report-15-Dec-2023", "report-01.03.2023"];
+    /// reports.humane_sort_with(&SortOptions::new().dates(true));
+    /// assert_eq!(reports, ["report-01.03.2023", "report-15-Dec-2023", "report-2-Jan-2024"]);
+    /// ```
+    pub fn dates(mut self, dates: bool) -> Self {
+        self.dates = dates;
+        self
+    }
+
+    /// Sets the character between the integer part and the fraction of decimal numbers, a dot
+    /// by default.
+    ///
+    /// Together with `thousands_separator` this reads numbers in the format of a locale, such
+    /// as "1.000,5" in German.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut prices = ["Preis 1.000,5", "Preis 999,99", "Preis 1.000,25"];
+    /// let german = SortOptions::new().decimals(true).decimal_separator(',').thousands_separator('.');
+    /// prices.humane_sort_with(&german);
+    /// assert_eq!(prices, ["Preis 999,99", "Preis 1.000,25", "Preis 1.000,5"]);
+    /// ```
+    pub fn decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = Some(separator);
+        self
+    }
+
+    /// Compares numbers with a decimal point, such as "1.25", as decimal numbers, so "1.10"
+    /// sorts before "1.2" and "1.5" equals "1.50".
+    ///
+    /// Only the first dot, or the character set with `decimal_separator`, between two numbers is a
+    /// decimal point, this is not meant for dotted version numbers such as "1.2.10".
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut readings = ["depth 1.2m", "depth 1.10m", "depth 1m", "depth 0.95m"];
+    /// readings.humane_sort_with(&SortOptions::new().decimals(true));
+    /// assert_eq!(readings, ["depth 0.95m", "depth 1m", "depth 1.10m", "depth 1.2m"]);
+    /// ```
+    pub fn decimals(mut self, decimals: bool) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Only treats digits as numbers if they are not directly adjacent to a letter.
+    ///
+    /// Digits that are part of a word, as in "mp3" or "x264", are then compared as text while
+    /// "track 3" or "track-3" still compare numerically.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut names = ["x264 b", "x3 a", "x10 a"];
+    /// names.humane_sort_with(&SortOptions::new().delimited_digits(true));
+    /// assert_eq!(names, ["x10 a", "x264 b", "x3 a"]);
+    /// ```
+    pub fn delimited_digits(mut self, delimited_digits: bool) -> Self {
+        self.delimited_digits = delimited_digits;
+        self
+    }
+
+    /// Compares durations such as "1h30m", "90m" or "02:15:00" by their length.
+    ///
+    /// Recognized are numbers with units, "d", "h", "min" or "m", "s" or "sec" and "ms", each
+    /// unit smaller than the one before, and clock times "M:SS" and "H:MM:SS" with an optional
+    /// fraction of a second. Durations compare with other numbers as their milliseconds.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut takes = ["take 1h30m", "take 45m", "take 02:15:00", "take 3:20"];
+    /// takes.humane_sort_with(&SortOptions::new().durations(true));
+    /// assert_eq!(takes, ["take 3:20", "take 45m", "take 1h30m", "take 02:15:00"]);
+    /// ```
+    pub fn durations(mut self, durations: bool) -> Self {
+        self.durations = durations;
+        self
+    }
+
+    /// Sets whether empty strings sort before or after all other strings.
+    ///
+    /// ```
+    /// use humanesort::{EmptyOrder, HumaneSortable, SortOptions};
+    /// let mut names = ["b", "", "a"];
+    /// names.humane_sort_with(&SortOptions::new().empty_order(EmptyOrder::Last));
+    /// assert_eq!(names, ["a", "b", ""]);
+    /// ```
+    pub fn empty_order(mut self, empty_order: EmptyOrder) -> Self {
+        self.empty_order = empty_order;
+        self
+    }
+
+    /// Sets whether file extensions are compared before or after the rest of the name.
+    ///
+    /// Names without an extension have an empty one.
+    ///
+    /// ```
+    /// use humanesort::{ExtensionOrder, HumaneSortable, SortOptions};
+    /// let mut names = ["a.zip", "b.txt", "a10.txt", "a9.txt", "Makefile"];
+    /// names.humane_sort_with(&SortOptions::new().extension_order(ExtensionOrder::First));
+    /// assert_eq!(names, ["Makefile", "a9.txt", "a10.txt", "b.txt", "a.zip"]);
+    /// let mut names = ["report 2.pdf", "report.txt", "report.pdf"];
+    /// names.humane_sort_with(&SortOptions::new().extension_order(ExtensionOrder::Last));
+    /// assert_eq!(names, ["report.pdf", "report.txt", "report 2.pdf"]);
+    /// ```
+    pub fn extension_order(mut self, extension_order: ExtensionOrder) -> Self {
+        self.extension_order = extension_order;
+        self
+    }
+
+    /// Sets which runs of hexadecimal digits are compared as numbers by their value.
+    ///
+    /// ```
+    /// use humanesort::{HexNumbers, HumaneSortable, SortOptions};
+    /// let mut dumps = ["dump_0x0A", "dump_0x2", "dump_0x1f"];
+    /// dumps.humane_sort_with(&SortOptions::new().hex_numbers(HexNumbers::Prefixed));
+    /// assert_eq!(dumps, ["dump_0x2", "dump_0x0A", "dump_0x1f"]);
+    /// ```
+    pub fn hex_numbers(mut self, hex_numbers: HexNumbers) -> Self {
+        self.hex_numbers = hex_numbers;
+        self
+    }
+
+    /// Compares letters without their diacritics, so "é" compares like "e" and "Ü" like "U".
+    ///
+    /// Text is decomposed into base letters and combining marks and the marks are skipped.
+    /// Letters that are not composed of a base letter and a mark, such as "ø" or "ł", stay
+    /// distinct. Strings that only differ in diacritics are ordered as without this option.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut names = ["Zoë", "Émile 10", "Emil", "émile 2", "Eva"];
+    /// names.humane_sort_with(&SortOptions::new().ignore_diacritics(true));
+    /// assert_eq!(names, ["Emil", "Émile 10", "Eva", "Zoë", "émile 2"]);
+    /// ```
+    pub fn ignore_diacritics(mut self, ignore_diacritics: bool) -> Self {
+        self.ignore_diacritics = ignore_diacritics;
+        self
+    }
+
+    /// Skips punctuation in text, so "read-me", "read_me" and "readme" are compared alike.
+    ///
+    /// Punctuation are ASCII punctuation and symbols and the common non-ASCII marks such as
+    /// dashes, quotation marks and CJK brackets. Minus signs and decimal points that are part of
+    /// a number are still recognized. Strings that only differ in punctuation are ordered as
+    /// without this option.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut titles = ["reader", "read_me", "readme", "read-me 2", "(read) me"];
+    /// titles.humane_sort_with(&SortOptions::new().ignore_punctuation(true));
+    /// assert_eq!(titles, ["(read) me", "reader", "read_me", "readme", "read-me 2"]);
+    /// ```
+    pub fn ignore_punctuation(mut self, ignore_punctuation: bool) -> Self {
+        self.ignore_punctuation = ignore_punctuation;
+        self
+    }
+
+    /// Compares IPv4 addresses such as "10.0.0.2" as single numbers, also where other options
+    /// would read parts of them as decimal numbers or dates.
+    ///
+    /// An address has four octets of one to three digits, separated by dots and not followed
+    /// by another dot and digit. IPv6 addresses are not recognized.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut hosts = ["web 10.0.0.10", "web 10.0.0.2", "web 9.255.0.1"];
+    /// hosts.humane_sort_with(&SortOptions::new().ip_addresses(true).decimals(true));
+    /// assert_eq!(hosts, ["web 9.255.0.1", "web 10.0.0.2", "web 10.0.0.10"]);
+    /// ```
+    pub fn ip_addresses(mut self, ip_addresses: bool) -> Self {
+        self.ip_addresses = ip_addresses;
+        self
+    }
+
+    /// Bounds the work done by a single comparison, see `Limits`.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets where NaN values are placed when comparing `f32` and `f64` values.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, NanOrder, SortOptions};
+    /// let mut scores = [2.5, f64::NAN, -1.0];
+    /// scores.humane_sort_with(&SortOptions::new().nan_order(NanOrder::First));
+    /// assert!(scores[0].is_nan());
+    /// assert_eq!(&scores[1..], &[-1.0, 2.5]);
+    /// ```
+    pub fn nan_order(mut self, nan_order: NanOrder) -> Self {
+        self.nan_order = nan_order;
+        self
+    }
+
+    /// Sets which minus signs before a number make it negative, negative numbers sort before
+    /// all other numbers.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, NegativeNumbers, SortOptions};
+    /// let mut readings = ["temp_3", "temp_-5", "temp_-12", "temp_0"];
+    /// readings.humane_sort_with(&SortOptions::new().negative_numbers(NegativeNumbers::Delimited));
+    /// assert_eq!(readings, ["temp_-12", "temp_-5", "temp_0", "temp_3"]);
+    /// ```
+    pub fn negative_numbers(mut self, negative_numbers: NegativeNumbers) -> Self {
+        self.negative_numbers = negative_numbers;
+        self
+    }
+
+    /// Sets whether numbers sort before or after text they are compared with, which decides
+    /// whether names starting with a number go before or after names starting with text.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, NumberOrder, SortOptions};
+    /// let mut names = ["10 outro", "intro", "2 verse", "bridge"];
+    /// names.humane_sort_with(&SortOptions::new().number_order(NumberOrder::Last));
+    /// assert_eq!(names, ["bridge", "intro", "2 verse", "10 outro"]);
+    /// ```
+    pub fn number_order(mut self, number_order: NumberOrder) -> Self {
+        self.number_order = number_order;
+        self
+    }
+
+    /// Compares outline numbers of document headings, such as "1.2.10" or "1.2.a", level by
+    /// level.
+    ///
+    /// An outline number is a number followed by levels of digits or a single letter, each after
+    /// a dot. Numeric levels compare by value and before letters, a heading sorts before the
+    /// headings below it. This takes precedence over `decimals`.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut headings = ["1.10 Appendix", "1.2.a Notes", "1.9 Summary", "1.2_draft", "1.2.1 Scope"];
+    /// headings.humane_sort_with(&SortOptions::new().outlines(true).decimals(true));
+    /// assert_eq!(headings, ["1.2_draft", "1.2.1 Scope", "1.2.a Notes", "1.9 Summary", "1.10 Appendix"]);
+    /// ```
+    pub fn outlines(mut self, outlines: bool) -> Self {
+        self.outlines = outlines;
+        self
+    }
+
+    /// Compares ranges such as "ep 1-3" or "pages 10–12" by their start and then their end.
+    ///
+    /// Two numbers separated by a hyphen, en dash or em dash form a range, a single number
+    /// sorts like a range that ends where it starts.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut pages = ["pages 10-12", "pages 10\u{2013}11", "pages 9"];
+    /// pages.humane_sort_with(&SortOptions::new().ranges(true));
+    /// assert_eq!(pages, ["pages 9", "pages 10\u{2013}11", "pages 10-12"]);
+    /// ```
+    pub fn ranges(mut self, ranges: bool) -> Self {
+        self.ranges = ranges;
+        self
+    }
+
+    /// Compares numbers in scientific notation, such as "1e3" or "2.5E-4", by their value.
+    ///
+    /// The exponent follows an "e" or "E" with an optional sign and has up to three digits. The
+    /// value compares with other numbers like a decimal number, so "1.5e2" equals "150".
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut samples = ["sample_3e2.csv", "sample_2.5E-4.csv", "sample_1e3.csv", "sample_8.csv"];
+    /// samples.humane_sort_with(&SortOptions::new().scientific(true));
+    /// assert_eq!(samples, ["sample_2.5E-4.csv", "sample_8.csv", "sample_3e2.csv", "sample_1e3.csv"]);
+    /// ```
+    pub fn scientific(mut self, scientific: bool) -> Self {
+        self.scientific = scientific;
+        self
+    }
+
+    /// Compares data sizes such as "500MB", "2 GiB" or "4K" by their number of bytes.
+    ///
+    /// Recognized units are "B" and the decimal ("kB", "MB", "GB") and binary ("KiB", "MiB",
+    /// "GiB") multiples up to exabytes. The "B" of multiples can be left out, as in the output
+    /// of `du -h`, unless a space separates the unit from the number.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut backups = ["backup 2GB", "backup 500MB", "backup 1.5 GiB", "backup 900M"];
+    /// backups.humane_sort_with(&SortOptions::new().sizes(true));
+    /// assert_eq!(backups, ["backup 500MB", "backup 900M", "backup 1.5 GiB", "backup 2GB"]);
+    /// ```
+    pub fn sizes(mut self, sizes: bool) -> Self {
+        self.sizes = sizes;
+        self
+    }
+
+    /// Reads numbers with thousands separators, such as "1,000,000" or "1 000", as a single
+    /// number.
+    ///
+    /// A number of up to three digits followed by groups of exactly three digits, each preceded
+    /// by `separator`, is read as one number. Other digits after a separator start a new number.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut rows = ["revenue 1,250,000", "revenue 980,500", "revenue 75"];
+    /// rows.humane_sort_with(&SortOptions::new().thousands_separator(','));
+    /// assert_eq!(rows, ["revenue 75", "revenue 980,500", "revenue 1,250,000"]);
+    /// ```
+    pub fn thousands_separator(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    /// Compares ISO 8601 timestamps with a UTC offset, such as "2024-05-01T10:00+02:00", as
+    /// points in time, so logs written in different time zones interleave chronologically.
+    ///
+    /// Seconds and fractions of a second are optional. Timestamps without an offset are local
+    /// times of an unknown zone and are compared like any other text.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut logs = ["2024-05-01T10:00+02:00.log", "2024-05-01T09:30Z.log"];
+    /// logs.humane_sort_with(&SortOptions::new().timestamps(true));
+    /// assert_eq!(logs, ["2024-05-01T10:00+02:00.log", "2024-05-01T09:30Z.log"]);
+    /// ```
+    pub fn timestamps(mut self, timestamps: bool) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Sets how whitespace in text is compared.
+    ///
+    /// Strings that are equal under the chosen treatment are ordered as with `Whitespace::Keep`,
+    /// so the order does not depend on the input order.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions, Whitespace};
+    /// let mut scans = ["scan 10", "scan  2", "scan 2b", "scan 2"];
+    /// scans.humane_sort_with(&SortOptions::new().whitespace(Whitespace::Collapse));
+    /// assert_eq!(scans, ["scan 2", "scan  2", "scan 2b", "scan 10"]);
+    /// let mut scans = ["scan10", "scan 2", "scan2"];
+    /// scans.humane_sort_with(&SortOptions::new().whitespace(Whitespace::Ignore));
+    /// assert_eq!(scans, ["scan2", "scan 2", "scan10"]);
+    /// ```
+    pub fn whitespace(mut self, whitespace: Whitespace) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// Sets the order of strings that are equal except for leading zeros of numbers.
+    ///
+    /// Leading zeros decide before case. The numbers are considered from left to right, the
+    /// first one with a different number of leading zeros decides.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions, ZeroOrder};
+    /// let mut names = ["file7", "file007", "file07"];
+    /// names.humane_sort_with(&SortOptions::new().zero_order(ZeroOrder::FewerFirst));
+    /// assert_eq!(names, ["file7", "file07", "file007"]);
+    /// names.humane_sort_with(&SortOptions::new().zero_order(ZeroOrder::MoreFirst));
+    /// assert_eq!(names, ["file007", "file07", "file7"]);
+    /// ```
+    pub fn zero_order(mut self, zero_order: ZeroOrder) -> Self {
+        self.zero_order = zero_order;
+        self
+    }
+
+    /// Whether text is normalized before it is compared, which makes distinct strings equal.
+    pub(crate) fn normalizes_text(&self) -> bool {
+        self.whitespace != Whitespace::Keep || self.ignore_diacritics || self.ignore_punctuation
+    }
+
+    /// Compares text with the collator of a locale while numbers are still compared by value.
+    ///
+    /// Text the collator considers equal is compared according to the case order.
+    #[cfg(feature = "icu")]
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.collation = Some(collation);
+        self
+    }
+
+    /// Compares text with the collator of the operating system while numbers are still compared
+    /// by value.
+    ///
+    /// This matches the ordering of the platform's file manager on Windows and macOS. On other
+    /// platforms, or if the collator fails, text is compared according to the case order.
+    #[cfg(feature = "native-collation")]
+    pub fn native_collation(mut self, native_collation: bool) -> Self {
+        self.native_collation = native_collation;
+        self
+    }
+
+    /// Compares text by its ASCII transliteration, so that text in different scripts sorts
+    /// into one predictable order.
+    ///
+    /// Text with the same transliteration is compared as without this option.
+    ///
+    /// ```
+    /// use humanesort::{HumaneSortable, SortOptions};
+    /// let mut names = ["Ωmega 2", "Beta 2", "Алфа 10"];
+    /// names.humane_sort_with(&SortOptions::new().transliterate(true));
+    /// assert_eq!(names, ["Алфа 10", "Beta 2", "Ωmega 2"]);
+    /// ```
+    #[cfg(feature = "any_ascii")]
+    pub fn transliterate(mut self, transliterate: bool) -> Self {
+        self.transliterate = transliterate;
+        self
+    }
+}
+
+/// Limits on the parts of a string a comparison looks at, for sorting untrusted input.
+///
+/// Pathological names, such as megabytes of alternating digits and letters, can make a
+/// comparison arbitrarily expensive. With limits set, only the first `max_len` bytes and the
+/// first `max_tokens` tokens of each string are compared humanely, and runs of more than
+/// `max_digits` digits are compared as text. Strings that are equal under these rules are
+/// ordered by their bytes, also if they did not exceed a limit, which keeps the order total.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use humanesort::{HumaneOrder, Limits, SortOptions};
+/// let options = SortOptions::new().limits(Limits::new().max_len(64).max_digits(20));
+/// let huge = "9".repeat(1000);
+/// // The digits of `huge` are compared as text, which sorts after numbers.
+/// assert_eq!(huge.as_str().humane_cmp_with("10", &options), Ordering::Greater);
+/// assert_eq!("file2".humane_cmp_with("file10", &options), Ordering::Less);
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct Limits {
+    pub(crate) max_len: Option<usize>,
+    pub(crate) max_tokens: Option<usize>,
+    pub(crate) max_digits: Option<usize>
+}
+
+impl Limits {
+    /// Creates limits that do not restrict anything.
+    pub fn new() -> Self {
+        Limits::default()
+    }
+
+    /// Only compares the first `max_len` bytes humanely, rounded down to a character boundary.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Only compares the first `max_tokens` tokens humanely.
+    pub fn max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Compares runs of more than `max_digits` bytes of digits as text.
+    pub fn max_digits(mut self, max_digits: usize) -> Self {
+        self.max_digits = Some(max_digits);
+        self
+    }
+
+    pub(crate) fn truncate<'a>(&self, s: &'a str) -> &'a str {
+        match self.max_len {
+            Some(max_len) if max_len < s.len() => {
+                // Cut at the last character boundary at or before `max_len`.
+                let end = (0..=max_len).rev().find(|&end| s.is_char_boundary(end)).unwrap_or(0);
+                &s[..end]
+            }
+            _ => s
+        }
+    }
+}
+
+/// Compares text ignoring case.
+pub(crate) fn compare_folded(a: &str, b: &str) -> Ordering {
+    a.chars().flat_map(char::to_lowercase).cmp(b.chars().flat_map(char::to_lowercase))
+}
+
+/// Breaks a tie between strings that compared equal ignoring case.
+///
+/// The case of all cased letters is compared in sequence, so the first difference in case
+/// decides.
+pub(crate) fn compare_case(a: &str, b: &str, order: CaseOrder) -> Ordering {
+    let cased = |c: &char| c.is_uppercase() || c.is_lowercase();
+    let upper_rank = match order {
+        CaseOrder::LowerFirst => 1,
+        _ => 0
+    };
+    let rank = |c: char| if c.is_uppercase() { upper_rank } else { 1 - upper_rank };
+    a.chars().filter(cased).map(rank).cmp(b.chars().filter(cased).map(rank))
+}
+
+#[cfg(test)]
+mod tests {
+    use {CaseOrder, HumaneSortable, SortOptions};
+
+    #[test]
+    fn case_precedence() {
+        let mut names = vec!["b", "apple 10", "Apple 2", "B", "apple 2", "A", "a"];
+        names.humane_sort();
+        assert_eq!(names, vec!["A", "Apple 2", "B", "a", "apple 2", "apple 10", "b"]);
+        names.humane_sort_with(&SortOptions::new().case_order(CaseOrder::UpperFirst));
+        assert_eq!(names, vec!["A", "a", "Apple 2", "apple 2", "apple 10", "B", "b"]);
+        names.humane_sort_with(&SortOptions::new().case_order(CaseOrder::LowerFirst));
+        assert_eq!(names, vec!["a", "A", "apple 2", "Apple 2", "apple 10", "b", "B"]);
+        let mut names = vec!["Zebra", "b", "apple", "B", "Apple"];
+        names.humane_sort_with(&SortOptions::new().case_order(CaseOrder::Ignore));
+        assert_eq!(names, vec!["apple", "Apple", "b", "B", "Zebra"]);
+    }
+
+    #[test]
+    fn placement_of_numbers_and_empty_strings() {
+        use {EmptyOrder, NumberOrder};
+        let mut names = vec!["x", "", "3", "x2", "xa", ""];
+        let options = SortOptions::new().number_order(NumberOrder::Last).empty_order(EmptyOrder::Last);
+        names.humane_sort_with(&options);
+        assert_eq!(names, vec!["x", "x2", "xa", "3", "", ""]);
+        names.humane_sort_with(&SortOptions::new().empty_order(EmptyOrder::Last));
+        assert_eq!(names, vec!["3", "x", "x2", "xa", "", ""]);
+    }
+
+    #[test]
+    fn extensions() {
+        use ExtensionOrder;
+        let mut names = vec![".zshrc", "b (2).txt", "b.txt", "a.md", ".bashrc", "Vol. 2.md"];
+        let by_type = SortOptions::new().extension_order(ExtensionOrder::First).copy_markers(true);
+        names.humane_sort_with(&by_type);
+        assert_eq!(names, vec![".bashrc", ".zshrc", "Vol. 2.md", "a.md", "b.txt", "b (2).txt"]);
+        names.humane_sort_with(&SortOptions::new().extension_order(ExtensionOrder::Last));
+        assert_eq!(names, vec![".bashrc", ".zshrc", "Vol. 2.md", "a.md", "b.txt", "b (2).txt"]);
+    }
+
+    #[test]
+    fn limits_keep_order_total() {
+        use std::cmp::Ordering;
+        use {HumaneOrder, Limits};
+        let options = SortOptions::new().limits(Limits::new().max_len(4).max_tokens(2));
+        assert_eq!("ab12x".humane_cmp_with("ab12y", &options), Ordering::Less);
+        assert_eq!("a01".humane_cmp_with("a1", &options), Ordering::Less);
+        assert_eq!("a1b2".humane_cmp_with("a1b1", &options), Ordering::Greater);
+        assert_eq!("é".humane_cmp_with("é", &SortOptions::new().limits(Limits::new().max_len(1))), Ordering::Equal);
+        let limits = Limits::new().max_len(3);
+        assert_eq!((limits.truncate("aéb"), limits.truncate("aéé"), limits.truncate("a€"), limits.truncate("ab")), ("aé", "aé", "a", "ab"));
+    }
+}
@@ -0,0 +1,205 @@
+//! Multi-threaded sorting built on `std::thread`, without additional dependencies.
+//!
+//! The input is split into runs that are sorted on a pool of scoped threads, the sorted runs
+//! are then merged pairwise, again in parallel.
+use std::cmp::{max, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use ::HumaneOrder;
+use progress::{CancelToken, Cancelled, Monitor, Progress};
+
+/// Chunks smaller than this are not worth spawning a thread for.
+const MIN_CHUNK_LEN: usize = 4096;
+
+/// Minimum number of runs for monitored sorts, which report progress and check for
+/// cancellation between runs.
+const PROGRESS_RUNS: usize = 64;
+
+/// Sorts `items` in humane order using up to `threads` threads.
+///
+/// Passing `0` uses the number of threads reported by `std::thread::available_parallelism`.
+/// The sort is stable.
+///
+/// ```
+/// use humanesort::parallel::humane_sort_parallel;
+/// let mut names: Vec<String> = (0..10000).rev().map(|i| format!("file-{}", i)).collect();
+/// humane_sort_parallel(&mut names, 4);
+/// assert_eq!(names[2], "file-2");
+/// assert_eq!(names[9999], "file-9999");
+/// ```
+pub fn humane_sort_parallel<T>(items: &mut [T], threads: usize) where T: HumaneOrder + Send {
+    // Without a monitor the sort cannot be cancelled.
+    let _ = sort_by_monitored(items, threads, &|a: &T, b: &T| a.humane_cmp(b), None);
+}
+
+/// Sorts `items` like `humane_sort_parallel`, reporting progress to `progress`.
+///
+/// ```
+/// use std::sync::atomic::{AtomicU8, Ordering};
+/// use humanesort::parallel::humane_sort_parallel_with_progress;
+/// let mut names: Vec<String> = (0..100000).rev().map(|i| format!("file-{}", i)).collect();
+/// let percent = AtomicU8::new(0);
+/// humane_sort_parallel_with_progress(&mut names, 4, |p| percent.store(p.percent, Ordering::Relaxed));
+/// assert_eq!(percent.into_inner(), 100);
+/// assert_eq!(names[10], "file-10");
+/// ```
+pub fn humane_sort_parallel_with_progress<T, F>(items: &mut [T], threads: usize, progress: F)
+    where T: HumaneOrder + Send, F: Fn(Progress) + Sync {
+    let monitor = Monitor::progress(&progress, sort_work(items.len(), threads));
+    // A progress monitor never cancels the sort.
+    let _ = sort_by_monitored(items, threads, &|a: &T, b: &T| a.humane_cmp(b), Some(&monitor));
+}
+
+/// Sorts `items` like `humane_sort_parallel`, stopping early once `cancel` is set.
+///
+/// The token is checked before every run is sorted and before every merge. A cancelled sort
+/// leaves `items` in an unspecified order.
+pub fn humane_sort_parallel_cancellable<T, C>(items: &mut [T], threads: usize, cancel: &C) -> Result<(), Cancelled>
+    where T: HumaneOrder + Send, C: CancelToken + Sync {
+    let monitor = Monitor::cancel(cancel);
+    sort_by_monitored(items, threads, &|a: &T, b: &T| a.humane_cmp(b), Some(&monitor))
+}
+
+/// Sorts `items` with the comparator `compare` using up to `threads` threads.
+///
+/// Like `humane_sort_parallel`, passing `0` uses all available threads and the sort is stable.
+///
+/// ```
+/// use humanesort::HumaneOrder;
+/// use humanesort::parallel::sort_by_parallel;
+/// let mut names: Vec<String> = (0..10000).map(|i| format!("file-{}", i)).collect();
+/// sort_by_parallel(&mut names, 4, |a, b| b.humane_cmp(a));
+/// assert_eq!(names[0], "file-9999");
+/// assert_eq!(names[9990], "file-9");
+/// ```
+pub fn sort_by_parallel<T, F>(items: &mut [T], threads: usize, compare: F) where T: Send, F: Fn(&T, &T) -> Ordering + Sync {
+    // Without a monitor the sort cannot be cancelled.
+    let _ = sort_by_monitored(items, threads, &compare, None);
+}
+
+pub(crate) fn thread_count(threads: usize) -> usize {
+    match threads {
+        0 => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        n => n
+    }
+}
+
+/// Returns the length of the initially sorted runs.
+fn first_run_len(len: usize, threads: usize, monitored: bool) -> usize {
+    let runs = match monitored {
+        true => max(threads, PROGRESS_RUNS),
+        false => threads
+    };
+    max(len.div_ceil(runs), MIN_CHUNK_LEN)
+}
+
+/// Returns the units of work reported while sorting `len` items with progress: every item is
+/// counted once when its run is sorted and once for every merge it takes part in.
+pub(crate) fn sort_work(len: usize, threads: usize) -> usize {
+    let mut run_len = first_run_len(len, thread_count(threads), true);
+    let mut work = len;
+    while run_len < len {
+        let rest = len % (run_len * 2);
+        work += len - rest;
+        if rest > run_len {
+            work += rest;
+        }
+        run_len *= 2;
+    }
+    work
+}
+
+/// Runs `work` on every task, using up to `threads` scoped threads, until the monitor reports
+/// a cancellation.
+fn for_each_parallel<I, F>(tasks: I, threads: usize, monitor: Option<&Monitor>, work: F) -> Result<(), Cancelled>
+    where I: Iterator + Send, I::Item: Send, F: Fn(I::Item) + Sync {
+    let check = || monitor.map_or(Ok(()), Monitor::check);
+    if threads == 1 {
+        for task in tasks {
+            check()?;
+            work(task);
+        }
+        return Ok(())
+    }
+    let tasks = Mutex::new(tasks);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| while check().is_ok() {
+                let task = tasks.lock().unwrap_or_else(|e| e.into_inner()).next();
+                match task {
+                    Some(task) => work(task),
+                    None => break
+                }
+            });
+        }
+    });
+    check()
+}
+
+/// Sorts `items` on up to `threads` threads. Only a monitor checking for cancellation can
+/// make this return an error.
+pub(crate) fn sort_by_monitored<T, F>(items: &mut [T], threads: usize, compare: &F, monitor: Option<&Monitor>) -> Result<(), Cancelled>
+    where T: Send, F: Fn(&T, &T) -> Ordering + Sync {
+    let threads = thread_count(threads);
+    let mut run_len = first_run_len(items.len(), threads, monitor.is_some());
+    if monitor.is_none() && (threads == 1 || items.len() <= run_len) {
+        items.sort_by(compare);
+        return Ok(())
+    }
+    let len = items.len();
+    for_each_parallel(items.chunks_mut(run_len), threads, monitor, |chunk| {
+        chunk.sort_by(compare);
+        if let Some(monitor) = monitor {
+            monitor.sorted(chunk.len());
+        }
+    })?;
+    while run_len < len {
+        // The stable sort detects the two presorted runs in each pair and merges them in
+        // linear time; merging adjacent runs keeps the overall sort stable.
+        let pairs = items.chunks_mut(run_len * 2).filter(|pair| pair.len() > run_len);
+        for_each_parallel(pairs, threads, monitor, |pair| {
+            pair.sort_by(compare);
+            if let Some(monitor) = monitor {
+                monitor.merged(pair.len());
+            }
+        })?;
+        run_len *= 2;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use super::{humane_sort_parallel, humane_sort_parallel_with_progress};
+    use ::HumaneSortable;
+
+    #[test]
+    fn matches_sequential_sort() {
+        let mut expected: Vec<String> = (0..10000).map(|i| format!("v{}.{}", (i * 7919) % 101, i % 13)).collect();
+        let mut actual = expected.clone();
+        expected.humane_sort();
+        humane_sort_parallel(&mut actual, 3);
+        assert_eq!(expected, actual);
+        let mut single = vec!["b", "a"];
+        humane_sort_parallel(&mut single, 0);
+        assert_eq!(single, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn progress_is_monotonic() {
+        let mut expected: Vec<String> = (0..20000).map(|i| format!("n{}", (i * 7919) % 20011)).collect();
+        let mut actual = expected.clone();
+        expected.humane_sort();
+        let seen = Mutex::new(Vec::new());
+        humane_sort_parallel_with_progress(&mut actual, 1, |p| seen.lock().unwrap().push(p));
+        assert_eq!(expected, actual);
+        let seen = seen.into_inner().unwrap();
+        // Five runs of 4096 elements are merged in four steps.
+        assert_eq!(seen.len(), 9);
+        assert!(seen.windows(2).all(|w| w[0].percent <= w[1].percent));
+        assert_eq!(seen.last().map(|p| (p.runs_merged, p.percent)), Some((4, 100)));
+        let mut empty: Vec<&str> = Vec::new();
+        humane_sort_parallel_with_progress(&mut empty, 2, |_| panic!("nothing to report"));
+    }
+}
@@ -0,0 +1,184 @@
+//! Humane ordering of paths.
+//!
+//! This module implements `HumaneOrder` for paths, path components and OS strings, and offers
+//! sorting of paths in tree order.
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Components, Path, PathBuf};
+use ::{HumaneOrder, SortOptions};
+
+/// Compares file names humanely, names that are not valid UTF-8 fall back to their raw
+/// representation for ties.
+pub(crate) fn compare_os_str(ours: &OsStr, theirs: &OsStr, options: &SortOptions) -> Ordering {
+    let (ours_text, theirs_text) = (ours.to_string_lossy(), theirs.to_string_lossy());
+    // Lossy conversion can map distinct names to the same string, the raw comparison keeps
+    // them apart. Valid names stay humane-equal like the strings, such as "a07" and "a7".
+    match ours_text.humane_cmp_with(&theirs_text, options) {
+        Ordering::Equal if matches!(ours_text, Cow::Owned(_)) || matches!(theirs_text, Cow::Owned(_)) => ours.cmp(theirs),
+        cmp => cmp
+    }
+}
+
+/// Normal components are compared humanely by name, all other components keep the order of
+/// `Component`'s `Ord` implementation.
+impl<'a> HumaneOrder for Component<'a> {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        self.humane_cmp_with(other, &SortOptions::default())
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        match (*self, *other) {
+            (Component::Normal(ours), Component::Normal(theirs)) => compare_os_str(ours, theirs, options),
+            _ => self.cmp(other)
+        }
+    }
+}
+
+/// Components are compared one at a time, so "dir2/file" sorts before "dir10/file".
+impl<'a> HumaneOrder for Components<'a> {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        self.humane_cmp_with(other, &SortOptions::default())
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        let mut ours = self.clone();
+        let mut theirs = other.clone();
+        loop {
+            match (ours.next(), theirs.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, _) => return Ordering::Less,
+                (_, None) => return Ordering::Greater,
+                (Some(a), Some(b)) => {
+                    let cmp = a.humane_cmp_with(&b, options);
+                    if cmp != Ordering::Equal {
+                        return cmp
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// OS strings are compared humanely by their lossy UTF-8 conversion, ties involving a name that
+/// is not valid UTF-8 are broken by the raw representation.
+impl HumaneOrder for OsStr {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        compare_os_str(self, other, &SortOptions::default())
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        compare_os_str(self, other, options)
+    }
+}
+
+impl HumaneOrder for OsString {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        self.as_os_str().humane_cmp(other)
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        self.as_os_str().humane_cmp_with(other, options)
+    }
+}
+
+/// Paths are compared by their components, like `Components`.
+///
+/// ```
+/// use std::path::PathBuf;
+/// use humanesort::HumaneSortable;
+/// let mut paths: Vec<PathBuf> = vec!["dir10/a".into(), "dir2/b".into(), "dir2".into()];
+/// paths.humane_sort();
+/// assert_eq!(paths, vec![PathBuf::from("dir2"), PathBuf::from("dir2/b"), PathBuf::from("dir10/a")]);
+/// ```
+impl HumaneOrder for Path {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        self.components().humane_cmp(&other.components())
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        self.components().humane_cmp_with(&other.components(), options)
+    }
+}
+
+impl HumaneOrder for PathBuf {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        self.as_path().humane_cmp(other)
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        self.as_path().humane_cmp_with(other, options)
+    }
+}
+
+/// Sorts `paths` in tree order: every directory comes directly before its contents and
+/// siblings within a directory are in humane order.
+///
+/// Paths are compared component by component, which keeps descendants of "a" together instead
+/// of interleaving them with siblings such as "a2" as a plain string sort would.
+///
+/// ```
+/// use humanesort::path::sort_tree;
+/// let mut paths = vec!["a10", "a/b10", "a2", "a/b2", "a", "a/b2/c"];
+/// sort_tree(&mut paths);
+/// assert_eq!(paths, vec!["a", "a/b2", "a/b2/c", "a/b10", "a2", "a10"]);
+/// ```
+pub fn sort_tree<P>(paths: &mut [P]) where P: AsRef<Path> {
+    sort_tree_with(paths, &SortOptions::default())
+}
+
+/// Sorts `paths` in tree order, comparing names with the rules configured in `options`.
+pub fn sort_tree_with<P>(paths: &mut [P], options: &SortOptions) where P: AsRef<Path> {
+    paths.sort_by(|a, b| a.as_ref().humane_cmp_with(b.as_ref(), options))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use std::path::{Component, Path};
+    use ::HumaneOrder;
+
+    #[test]
+    fn compare_components() {
+        let a = Path::new("dir2/file").components();
+        let b = Path::new("dir10/file").components();
+        assert_eq!(a.humane_cmp(&b), Ordering::Less);
+        let short = Path::new("dir2").components();
+        assert_eq!(short.humane_cmp(&a), Ordering::Less);
+        let root = Path::new("/").components().next().unwrap();
+        let normal = Path::new("a").components().next().unwrap();
+        assert_eq!(root.humane_cmp(&normal), Ordering::Less);
+        let x = Component::Normal("x-11".as_ref());
+        let y = Component::Normal("x-9".as_ref());
+        assert_eq!(x.humane_cmp(&y), Ordering::Greater);
+    }
+
+    #[test]
+    fn equal_like_strings() {
+        assert_eq!(Path::new("a07").humane_cmp(Path::new("a7")), "a07".humane_cmp("a7"));
+        assert_eq!(Path::new("dir/a07").humane_cmp(Path::new("dir/a7")), Ordering::Equal);
+        assert_eq!(Path::new("a07").humane_cmp(Path::new("a8")), "a07".humane_cmp("a8"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_names() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let invalid = OsStr::from_bytes(b"file-10\xff");
+        let replaced = OsStr::new("file-10\u{FFFD}");
+        assert_eq!(OsStr::new("file-9").humane_cmp(invalid), Ordering::Less);
+        assert_eq!(invalid.humane_cmp(replaced), invalid.cmp(replaced));
+        assert_ne!(invalid.humane_cmp(replaced), Ordering::Equal);
+    }
+
+    #[test]
+    fn tree_order() {
+        use std::path::PathBuf;
+        use super::sort_tree;
+        let mut paths: Vec<PathBuf> = vec!["/x/y-1", "/x-1", "/x/", "/", "/x/y-1/z"].into_iter().map(PathBuf::from).collect();
+        sort_tree(&mut paths);
+        let sorted: Vec<_> = paths.iter().map(|p| p.to_str().unwrap()).collect();
+        assert_eq!(sorted, vec!["/", "/x/", "/x/y-1", "/x/y-1/z", "/x-1"]);
+    }
+}
@@ -1,2 +1,7 @@
 pub use ::HumaneSortable as HumaneSortable;
 pub use ::HumaneOrder as HumaneOrder;
+pub use ::HumaneSortableByKey as HumaneSortableByKey;
+pub use ::HumaneSearchable as HumaneSearchable;
+pub use ::iter::HumaneIterator as HumaneIterator;
+pub use ::HumaneStr as HumaneStr;
+pub use ::HumaneString as HumaneString;
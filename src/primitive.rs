@@ -0,0 +1,75 @@
+//! `HumaneOrder` implementations for primitive types.
+use std::cmp::Ordering;
+use ::{HumaneOrder, NanOrder, SortOptions};
+
+macro_rules! impl_humane_order_for_float {
+    ($($t:ty),*) => {
+        $(
+            /// Floats are ordered by `total_cmp`, NaN placement can be configured with
+            /// `SortOptions::nan_order`.
+            impl HumaneOrder for $t {
+                fn humane_cmp(&self, other: &Self) -> Ordering {
+                    self.total_cmp(other)
+                }
+
+                fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+                    match (options.nan_order, self.is_nan(), other.is_nan()) {
+                        (NanOrder::Sign, _, _) | (_, false, false) => self.total_cmp(other),
+                        (_, true, true) => Ordering::Equal,
+                        (NanOrder::First, true, false) | (NanOrder::Last, false, true) => Ordering::Less,
+                        (NanOrder::First, false, true) | (NanOrder::Last, true, false) => Ordering::Greater
+                    }
+                }
+            }
+        )*
+    }
+}
+
+impl_humane_order_for_float!(f32, f64);
+
+macro_rules! impl_humane_order_for_ord {
+    ($($t:ty),*) => {
+        $(
+            /// Integers and other primitives without a textual representation are ordered by
+            /// their `Ord` implementation.
+            impl HumaneOrder for $t {
+                fn humane_cmp(&self, other: &Self) -> Ordering {
+                    self.cmp(other)
+                }
+            }
+        )*
+    }
+}
+
+impl_humane_order_for_ord!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, bool);
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use std::f64;
+    use ::{HumaneOrder, HumaneSortable, NanOrder, SortOptions};
+
+    #[test]
+    fn nan_placement() {
+        let mut values = [1.0, -f64::NAN, 0.5, f64::NAN, -0.0, 0.0];
+        values.humane_sort();
+        assert!(values[0].is_nan() && values[5].is_nan());
+        assert_eq!(&values[1..5], &[-0.0, 0.0, 0.5, 1.0]);
+        values.humane_sort_with(&SortOptions::new().nan_order(NanOrder::Last));
+        assert!(values[4].is_nan() && values[5].is_nan());
+        assert_eq!(f32::NAN.humane_cmp_with(&-f32::NAN, &SortOptions::new().nan_order(NanOrder::First)), Ordering::Equal);
+    }
+
+    #[test]
+    fn integers() {
+        fn smallest<T: HumaneOrder + Copy>(values: &[T]) -> T {
+            let mut sorted = values.to_vec();
+            sorted.humane_sort();
+            sorted[0]
+        }
+        assert_eq!(smallest(&[10u8, 9, 200]), 9);
+        assert_eq!(smallest(&[-3i64, 2, i64::MIN]), i64::MIN);
+        assert!(!smallest(&[true, false]));
+        assert_eq!(7usize.humane_cmp_with(&10, &SortOptions::new().decimals(true)), Ordering::Less);
+    }
+}
@@ -0,0 +1,181 @@
+//! Progress reporting and cancellation for long-running sorts.
+//!
+//! The `_with_progress` variants of the large-sort functions call a callback with a `Progress`
+//! value every time a unit of work finishes, so applications can show a progress bar. The
+//! callback may be called from worker threads, but never concurrently.
+//!
+//! The `_cancellable` variants check a `CancelToken` between units of work and stop early with
+//! `Cancelled` once it is set.
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::Mutex;
+
+/// A snapshot of the work done by a sort.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct Progress {
+    /// Number of elements whose sort keys have been computed.
+    pub keyed: usize,
+    /// Number of sorted runs merged so far.
+    pub runs_merged: usize,
+    /// Share of the total work done, from 0 to 100.
+    pub percent: u8
+}
+
+/// Tells a sort whether it should stop early.
+///
+/// ```
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use humanesort::parallel::humane_sort_parallel_cancellable;
+/// use humanesort::progress::Cancelled;
+/// let mut names: Vec<String> = (0..100000).map(|i| format!("file-{}", i)).collect();
+/// let cancel = AtomicBool::new(true);
+/// assert_eq!(humane_sort_parallel_cancellable(&mut names, 2, &cancel), Err(Cancelled));
+/// cancel.store(false, Ordering::Relaxed);
+/// assert_eq!(humane_sort_parallel_cancellable(&mut names, 2, &|| false), Ok(()));
+/// ```
+pub trait CancelToken {
+    /// Returns `true` once the sort should stop.
+    fn is_cancelled(&self) -> bool;
+}
+
+impl CancelToken for AtomicBool {
+    fn is_cancelled(&self) -> bool {
+        self.load(atomic::Ordering::Relaxed)
+    }
+}
+
+impl<F> CancelToken for F where F: Fn() -> bool {
+    fn is_cancelled(&self) -> bool {
+        self()
+    }
+}
+
+/// The error returned by a sort that was cancelled before it finished.
+///
+/// The slice still holds all of its elements, but in an unspecified order.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("sort was cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+struct State {
+    progress: Progress,
+    done: usize
+}
+
+/// Observes a sort, passing work done to the progress callback and checking for cancellation.
+pub(crate) struct Monitor<'a> {
+    callback: Option<&'a (dyn Fn(Progress) + Sync)>,
+    cancel: Option<&'a (dyn CancelToken + Sync)>,
+    cancelled: AtomicBool,
+    total: usize,
+    state: Mutex<State>
+}
+
+impl<'a> Monitor<'a> {
+    /// Creates a monitor reporting progress for a sort consisting of `total` units of work.
+    pub(crate) fn progress(callback: &'a (dyn Fn(Progress) + Sync), total: usize) -> Self {
+        Monitor { callback: Some(callback), ..Monitor::new(total) }
+    }
+
+    /// Creates a monitor that stops the sort once `cancel` is set.
+    pub(crate) fn cancel(cancel: &'a (dyn CancelToken + Sync)) -> Self {
+        Monitor { cancel: Some(cancel), ..Monitor::new(0) }
+    }
+
+    fn new(total: usize) -> Self {
+        Monitor {
+            callback: None,
+            cancel: None,
+            cancelled: AtomicBool::new(false),
+            total,
+            state: Mutex::new(State { progress: Progress::default(), done: 0 })
+        }
+    }
+
+    /// Returns `Err(Cancelled)` if the sort should stop. Once cancelled, it stays cancelled.
+    pub(crate) fn check(&self) -> Result<(), Cancelled> {
+        if self.cancelled.load(atomic::Ordering::Relaxed) {
+            return Err(Cancelled)
+        }
+        match self.cancel {
+            Some(cancel) if cancel.is_cancelled() => {
+                self.cancelled.store(true, atomic::Ordering::Relaxed);
+                Err(Cancelled)
+            }
+            _ => Ok(())
+        }
+    }
+
+    /// Records that `count` more elements have been keyed.
+    pub(crate) fn keyed(&self, count: usize) {
+        self.advance(count, |progress| progress.keyed += count)
+    }
+
+    /// Records that a run of `len` elements has been sorted.
+    pub(crate) fn sorted(&self, len: usize) {
+        self.advance(len, |_| {})
+    }
+
+    /// Records that two runs with a total of `len` elements have been merged.
+    pub(crate) fn merged(&self, len: usize) {
+        self.advance(len, |progress| progress.runs_merged += 1)
+    }
+
+    fn advance<F>(&self, work: usize, update: F) where F: FnOnce(&mut Progress) {
+        let callback = match self.callback {
+            Some(callback) => callback,
+            None => return
+        };
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.done += work;
+        update(&mut state.progress);
+        state.progress.percent = match self.total {
+            0 => 100,
+            total => (state.done.min(total) * 100 / total) as u8
+        };
+        // The lock is held while calling back so that updates arrive in order.
+        callback(state.progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use super::{Cancelled, Monitor, Progress};
+
+    #[test]
+    fn percent_of_total() {
+        let seen = Mutex::new(Vec::new());
+        let callback = |p: Progress| seen.lock().unwrap().push(p);
+        let monitor = Monitor::progress(&callback, 40);
+        monitor.keyed(10);
+        monitor.sorted(10);
+        monitor.merged(20);
+        assert_eq!(monitor.check(), Ok(()));
+        assert_eq!(seen.into_inner().unwrap(), vec![
+            Progress { keyed: 10, runs_merged: 0, percent: 25 },
+            Progress { keyed: 10, runs_merged: 0, percent: 50 },
+            Progress { keyed: 10, runs_merged: 1, percent: 100 },
+        ]);
+    }
+
+    #[test]
+    fn cancellation_latches() {
+        let calls = AtomicUsize::new(0);
+        let cancel = || calls.fetch_add(1, Ordering::Relaxed) >= 1;
+        let monitor = Monitor::cancel(&cancel);
+        assert_eq!(monitor.check(), Ok(()));
+        assert_eq!(monitor.check(), Err(Cancelled));
+        assert_eq!(monitor.check(), Err(Cancelled));
+        assert_eq!(calls.into_inner(), 2);
+    }
+}
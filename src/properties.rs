@@ -0,0 +1,101 @@
+//! Sorting `KEY=VALUE` files such as `.env` and Java properties files by key.
+//!
+//! Every entry line is kept intact, only the order of entries changes. Blank lines and comments
+//! stay where they are, unless comments are attached to the entry below them.
+use std::string::String;
+use std::vec::Vec;
+use ::HumaneOrder;
+
+/// Returns the key of an entry line, or `None` for blank lines and comments.
+///
+/// The key ends at the first `=`, `:` or whitespace, a leading `export ` is skipped.
+fn entry_key(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+        return None
+    }
+    let line = line.strip_prefix("export ").map_or(line, str::trim_start);
+    let end = line.find(|c: char| c == '=' || c == ':' || c.is_whitespace()).unwrap_or(line.len());
+    Some(&line[..end])
+}
+
+/// Sorts the entries of `input` in humane order of their keys.
+///
+/// With `attach_comments`, comment lines directly above an entry, without a blank line in
+/// between, move together with it. Entries with equal keys keep their order. Windows line
+/// endings are kept if the input uses them.
+///
+/// ```
+/// use humanesort::properties::sort_by_key;
+/// let input = "# Workers\nWORKER_10=c\n# The first worker\nWORKER_2=b\n\nAPP_NAME=demo\n";
+/// assert_eq!(sort_by_key(input, true), "APP_NAME=demo\n# The first worker\nWORKER_2=b\n\n# Workers\nWORKER_10=c\n");
+/// assert_eq!(sort_by_key(input, false), "# Workers\nAPP_NAME=demo\n# The first worker\nWORKER_2=b\n\nWORKER_10=c\n");
+/// ```
+pub fn sort_by_key(input: &str, attach_comments: bool) -> String {
+    let newline = match input.contains("\r\n") {
+        true => "\r\n",
+        false => "\n"
+    };
+    let lines: Vec<&str> = input.lines().collect();
+    // The file is split into lines that stay in place and units of an entry together with its
+    // attached comments; the sorted units are then put into the slots of the original units.
+    let mut slots: Vec<Option<&str>> = Vec::new();
+    let mut units: Vec<(&str, Vec<&str>)> = Vec::new();
+    let mut pending_comments = 0;
+    for line in &lines {
+        match entry_key(line) {
+            Some(key) => {
+                let start = slots.len() - pending_comments;
+                let unit = slots.drain(start..).flatten().chain(Some(*line)).collect();
+                slots.push(None);
+                units.push((key, unit));
+                pending_comments = 0;
+            }
+            None => {
+                let comment = !line.trim().is_empty();
+                pending_comments = match attach_comments && comment {
+                    true => pending_comments + 1,
+                    false => 0
+                };
+                slots.push(Some(line));
+            }
+        }
+    }
+    units.sort_by(|a, b| a.0.humane_cmp(b.0));
+    let mut units = units.into_iter();
+    let mut output = String::with_capacity(input.len() + 1);
+    for slot in slots {
+        let unit = match slot {
+            Some(line) => vec![line],
+            None => units.next().map(|unit| unit.1).unwrap_or_default()
+        };
+        for line in unit {
+            output.push_str(line);
+            output.push_str(newline);
+        }
+    }
+    if !input.ends_with('\n') {
+        output.truncate(output.len().saturating_sub(newline.len()));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{entry_key, sort_by_key};
+
+    #[test]
+    fn keys() {
+        assert_eq!(entry_key("  export PORT_8=1"), Some("PORT_8"));
+        assert_eq!(entry_key("server.port: 8080"), Some("server.port"));
+        assert_eq!(entry_key("! comment"), None);
+        assert_eq!(entry_key("   "), None);
+    }
+
+    #[test]
+    fn keeps_line_endings() {
+        assert_eq!(sort_by_key("b=1\r\na=2", false), "a=2\r\nb=1");
+        assert_eq!(sort_by_key("", true), "");
+        assert_eq!(sort_by_key("x10=\nx9=\nx9=dup\n", false), "x9=\nx9=dup\nx10=\n");
+    }
+}
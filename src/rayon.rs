@@ -0,0 +1,75 @@
+//! Parallel sorting on the rayon thread pool.
+//!
+//! This module is only available with the `rayon` feature. Unlike the `parallel` module it
+//! shares rayon's global pool with the rest of the application.
+use librayon::prelude::*;
+use key::{apply_permutation, KeyArena};
+use ::{HumaneOrder, SortOptions};
+
+/// Trait for slices that can be sorted in humane order on the rayon thread pool.
+///
+/// ```
+/// use humanesort::rayon::ParallelHumaneSortable;
+/// let mut names: Vec<String> = (0..10000).rev().map(|i| format!("app-{}.log", i)).collect();
+/// names.par_humane_sort();
+/// assert_eq!(names[10], "app-10.log");
+/// ```
+pub trait ParallelHumaneSortable {
+    /// Sorts in humane order, the sort is stable.
+    fn par_humane_sort(&mut self);
+    /// Sorts using the comparison rules configured in `options`.
+    fn par_humane_sort_with(&mut self, options: &SortOptions);
+    /// Sorts without preserving the order of humane-equal elements.
+    fn par_humane_sort_unstable(&mut self);
+}
+
+impl<T> ParallelHumaneSortable for [T] where T: HumaneOrder + Send {
+    fn par_humane_sort(&mut self) {
+        self.par_sort_by(|a, b| a.humane_cmp(b))
+    }
+
+    fn par_humane_sort_with(&mut self, options: &SortOptions) {
+        self.par_sort_by(|a, b| a.humane_cmp_with(b, options))
+    }
+
+    fn par_humane_sort_unstable(&mut self) {
+        self.par_sort_unstable_by(|a, b| a.humane_cmp(b))
+    }
+}
+
+/// Sorts `items` in humane order like `key::humane_sort_precomputed`, tokenizing the items
+/// and sorting the keys in parallel.
+///
+/// ```
+/// use humanesort::rayon::par_humane_sort_precomputed;
+/// let mut names: Vec<String> = (0..10000).rev().map(|i| format!("app-{}.log", i)).collect();
+/// par_humane_sort_precomputed(&mut names);
+/// assert_eq!(names[9999], "app-9999.log");
+/// ```
+pub fn par_humane_sort_precomputed<S>(items: &mut [S]) where S: AsRef<str> + Sync {
+    let indices = {
+        let arena = KeyArena::new_parallel(items);
+        let mut indices: Vec<usize> = (0..arena.len()).collect();
+        indices.par_sort_by(|&a, &b| arena.compare(a, b));
+        indices
+    };
+    apply_permutation(items, indices);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{par_humane_sort_precomputed, ParallelHumaneSortable};
+    use ::HumaneSortable;
+
+    #[test]
+    fn matches_sequential_sort() {
+        let mut expected: Vec<String> = (0..20000).map(|i| format!("x{}y{}", i * 7919 % 1000, i % 13)).collect();
+        let mut stable = expected.clone();
+        let mut keyed = expected.clone();
+        expected.humane_sort();
+        stable.par_humane_sort();
+        par_humane_sort_precomputed(&mut keyed);
+        assert_eq!(stable, expected);
+        assert_eq!(keyed, expected);
+    }
+}
@@ -0,0 +1,158 @@
+//! Scanning of ASCII text for the tokenizer.
+//!
+//! With the `simd` feature 16 bytes are classified at a time with SSE2 on x86 and NEON on
+//! AArch64, both are part of the baseline of these targets and need no runtime detection. Other
+//! targets and the ends of the text use the scalar loops.
+
+/// Returns the length of the run at the start of `bytes` that consists of ASCII digits if
+/// `digits` is set, or of anything but ASCII digits otherwise.
+pub(crate) fn run_len(bytes: &[u8], digits: bool) -> usize {
+    let start = simd::run_len(bytes, digits);
+    start + bytes[start..].iter().take_while(|b| b.is_ascii_digit() == digits).count()
+}
+
+/// Returns the length of the longest common prefix of `a` and `b`.
+pub(crate) fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    let start = simd::common_prefix(a, b);
+    start + a[start..].iter().zip(&b[start..]).take_while(|&(a, b)| a == b).count()
+}
+
+/// The SIMD versions return how far whole chunks of 16 bytes got before finding a difference,
+/// the scalar loops finish from there.
+#[cfg(all(feature = "simd", any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+mod simd {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    pub fn run_len(bytes: &[u8], digits: bool) -> usize {
+        let mut i = 0;
+        while i + 16 <= bytes.len() {
+            // The load is within bounds and SSE2 is enabled for the target.
+            let mask = unsafe {
+                let chunk = _mm_loadu_si128(bytes.as_ptr().add(i) as *const __m128i);
+                let digit = _mm_and_si128(_mm_cmpgt_epi8(chunk, _mm_set1_epi8(b'0' as i8 - 1)),
+                                          _mm_cmplt_epi8(chunk, _mm_set1_epi8(b'9' as i8 + 1)));
+                _mm_movemask_epi8(digit) as u32
+            };
+            let other = match digits {
+                true => !mask & 0xFFFF,
+                false => mask
+            };
+            if other != 0 {
+                return i + other.trailing_zeros() as usize
+            }
+            i += 16;
+        }
+        i
+    }
+
+    pub fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+        let len = a.len().min(b.len());
+        let mut i = 0;
+        while i + 16 <= len {
+            // Both loads are within bounds and SSE2 is enabled for the target.
+            let equal = unsafe {
+                let ours = _mm_loadu_si128(a.as_ptr().add(i) as *const __m128i);
+                let theirs = _mm_loadu_si128(b.as_ptr().add(i) as *const __m128i);
+                _mm_movemask_epi8(_mm_cmpeq_epi8(ours, theirs)) as u32
+            };
+            if equal != 0xFFFF {
+                return i + (!equal).trailing_zeros() as usize
+            }
+            i += 16;
+        }
+        i
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "aarch64", target_feature = "neon"))]
+mod simd {
+    use std::arch::aarch64::*;
+
+    /// Narrows a comparison result to 4 bits per byte, NEON has no byte mask instruction.
+    unsafe fn nibble_mask(result: uint8x16_t) -> u64 {
+        vget_lane_u64(vreinterpret_u64_u8(vshrn_n_u16(vreinterpretq_u16_u8(result), 4)), 0)
+    }
+
+    pub fn run_len(bytes: &[u8], digits: bool) -> usize {
+        let mut i = 0;
+        while i + 16 <= bytes.len() {
+            // The load is within bounds and NEON is enabled for the target.
+            let mask = unsafe {
+                let chunk = vld1q_u8(bytes.as_ptr().add(i));
+                let digit = vcleq_u8(vsubq_u8(chunk, vdupq_n_u8(b'0')), vdupq_n_u8(9));
+                nibble_mask(digit)
+            };
+            let other = match digits {
+                true => !mask,
+                false => mask
+            };
+            if other != 0 {
+                return i + other.trailing_zeros() as usize / 4
+            }
+            i += 16;
+        }
+        i
+    }
+
+    pub fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+        let len = a.len().min(b.len());
+        let mut i = 0;
+        while i + 16 <= len {
+            // Both loads are within bounds and NEON is enabled for the target.
+            let different = unsafe {
+                nibble_mask(vmvnq_u8(vceqq_u8(vld1q_u8(a.as_ptr().add(i)), vld1q_u8(b.as_ptr().add(i)))))
+            };
+            if different != 0 {
+                return i + different.trailing_zeros() as usize / 4
+            }
+            i += 16;
+        }
+        i
+    }
+}
+
+#[cfg(not(all(feature = "simd", any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+    all(target_arch = "aarch64", target_feature = "neon")
+))))]
+mod simd {
+    pub fn run_len(_: &[u8], _: bool) -> usize {
+        0
+    }
+
+    pub fn common_prefix(_: &[u8], _: &[u8]) -> usize {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{common_prefix, run_len};
+
+    #[test]
+    fn runs_across_chunks() {
+        let text = b"0123456789012345678901234567890123456789abcdefghijklmnopqrstuvwxyz/:09";
+        for start in 0..text.len() {
+            let digits = text[start].is_ascii_digit();
+            let expected = text[start..].iter().take_while(|b| b.is_ascii_digit() == digits).count();
+            assert_eq!(run_len(&text[start..], digits), expected, "{}", start);
+        }
+        assert_eq!(run_len(b"", true), 0);
+        assert_eq!(run_len(b"\x7F\x80\xFF", false), 3);
+    }
+
+    #[test]
+    fn prefixes_across_chunks() {
+        let a = [b'x'; 40];
+        for i in 0..a.len() {
+            let mut b = a;
+            b[i] = b'y';
+            assert_eq!(common_prefix(&a, &b), i);
+            assert_eq!(common_prefix(&a[..i], &a), i);
+        }
+        assert_eq!(common_prefix(&a, &a), a.len());
+    }
+}
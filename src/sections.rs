@@ -0,0 +1,156 @@
+//! Section indexes for sorted lists, such as the jump bar of a contact list.
+use std::ops::Range;
+use std::string::{String, ToString};
+use std::vec::Vec;
+use unicode_segmentation::UnicodeSegmentation;
+use ::{HumaneOrder, SortOptions};
+
+/// Label of the section for names starting with a number.
+pub const NUMBERS: &str = "0–9";
+/// Label of the section for names starting with neither a letter nor a number.
+pub const OTHER: &str = "#";
+
+/// A run of names in a sorted list sharing the same header.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Section {
+    /// The header, an uppercase letter, `NUMBERS` or `OTHER`.
+    pub label: String,
+    /// The positions of the names of the section in the sorted list.
+    pub range: Range<usize>
+}
+
+/// Returns the section header `name` belongs under.
+///
+/// ```
+/// use humanesort::sections::label;
+/// assert_eq!(label("émile"), "É");
+/// assert_eq!(label("1️⃣ intro"), "0–9");
+/// assert_eq!(label("_config"), "#");
+/// ```
+pub fn label(name: &str) -> String {
+    let first = match name.graphemes(true).next() {
+        Some(first) => first,
+        None => return OTHER.to_string()
+    };
+    if ::grapheme_digits(first).is_some() {
+        return NUMBERS.to_string()
+    }
+    match first.chars().next() {
+        Some(c) if c.is_alphabetic() => c.to_uppercase().collect(),
+        _ => OTHER.to_string()
+    }
+}
+
+/// Sorts `names` with the rules configured in `options` and returns the sections of the
+/// sorted list.
+///
+/// Sections follow the sorted order and adjacent names with the same label share a section.
+/// Names stay where the ordering puts them, so a label can occur more than once when its names
+/// are not adjacent. With the default code point order this happens for letters of different
+/// case, ordering letters alphabetically with `CaseOrder::UpperFirst` or `LowerFirst` gives
+/// every letter a single section.
+///
+/// ```
+/// use humanesort::{CaseOrder, SortOptions};
+/// use humanesort::sections::section_index;
+/// let mut names = ["bob", "Anna", "2pac", "10cc", "alice"];
+/// let sections = section_index(&mut names, &SortOptions::new().case_order(CaseOrder::UpperFirst));
+/// assert_eq!(names, ["2pac", "10cc", "alice", "Anna", "bob"]);
+/// let labels: Vec<_> = sections.iter().map(|s| (s.label.as_str(), s.range.clone())).collect();
+/// assert_eq!(labels, vec![("0–9", 0..2), ("A", 2..4), ("B", 4..5)]);
+/// ```
+pub fn section_index<S>(names: &mut [S], options: &SortOptions) -> Vec<Section> where S: AsRef<str> {
+    names.sort_by(|a, b| a.as_ref().humane_cmp_with(b.as_ref(), options));
+    let mut sections: Vec<Section> = Vec::new();
+    for (index, name) in names.iter().enumerate() {
+        let label = label(name.as_ref());
+        match sections.last_mut() {
+            Some(section) if section.label == label => section.range.end = index + 1,
+            _ => sections.push(Section { label, range: index..index + 1 })
+        }
+    }
+    sections
+}
+
+/// Returns the text of `name` before its first number, or all of it if it has none.
+///
+/// ```
+/// use humanesort::sections::prefix;
+/// assert_eq!(prefix("img-0042.jpg"), "img-");
+/// assert_eq!(prefix("README"), "README");
+/// ```
+pub fn prefix(name: &str) -> &str {
+    let end = name.grapheme_indices(true).find(|&(_, g)| ::grapheme_digits(g).is_some());
+    &name[..end.map_or(name.len(), |(index, _)| index)]
+}
+
+/// Iterator returned by `group_humane_by_prefix`.
+pub struct PrefixGroups<'a, S> where S: 'a {
+    rest: &'a [S]
+}
+
+impl<'a, S> Iterator for PrefixGroups<'a, S> where S: AsRef<str> {
+    type Item = (&'a str, &'a [S]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = prefix(self.rest.first()?.as_ref());
+        let len = self.rest.iter().take_while(|name| prefix(name.as_ref()) == first).count();
+        let (group, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some((first, group))
+    }
+}
+
+/// Splits a humane-sorted slice into runs of names with the same `prefix`.
+///
+/// Like `section_index`, groups follow the order of the slice and a prefix occurs more than
+/// once when its names are not adjacent.
+///
+/// ```
+/// use humanesort::HumaneSortable;
+/// use humanesort::sections::group_humane_by_prefix;
+/// let mut names = ["scan-2.png", "img-10.jpg", "img-9.jpg", "notes.txt"];
+/// names.humane_sort();
+/// let groups: Vec<_> = group_humane_by_prefix(&names).collect();
+/// assert_eq!(groups, vec![
+///     ("img-", &["img-9.jpg", "img-10.jpg"][..]),
+///     ("notes.txt", &["notes.txt"][..]),
+///     ("scan-", &["scan-2.png"][..]),
+/// ]);
+/// ```
+pub fn group_humane_by_prefix<S>(sorted: &[S]) -> PrefixGroups<'_, S> where S: AsRef<str> {
+    PrefixGroups { rest: sorted }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+    use super::{group_humane_by_prefix, prefix, section_index};
+    use ::{CaseOrder, SortOptions};
+
+    #[test]
+    fn runs_of_labels() {
+        let mut names = vec!["Bob", "alice", "#tag", "", "zed", "Émile"];
+        let labels = |names: &mut Vec<&str>, options| section_index(names, options).into_iter()
+            .map(|section| (section.label, section.range.len())).collect::<Vec<_>>();
+        let folded = SortOptions::new().case_order(CaseOrder::LowerFirst);
+        assert_eq!(labels(&mut names, &folded), vec![
+            ("#".to_string(), 2), ("A".to_string(), 1), ("B".to_string(), 1), ("Z".to_string(), 1), ("É".to_string(), 1)
+        ]);
+        assert_eq!(labels(&mut names, &SortOptions::new()), vec![
+            ("#".to_string(), 2), ("B".to_string(), 1), ("A".to_string(), 1), ("Z".to_string(), 1), ("É".to_string(), 1)
+        ]);
+        assert_eq!(section_index(&mut Vec::<String>::new(), &SortOptions::new()), vec![]);
+    }
+
+    #[test]
+    fn prefix_groups() {
+        assert_eq!(prefix("v٣.1"), "v");
+        assert_eq!(prefix(""), "");
+        let names = ["cd1", "cd2", "dvd", "dvd1", "dvd1x"];
+        let prefixes: Vec<_> = group_humane_by_prefix(&names).map(|(prefix, group)| (prefix, group.len())).collect();
+        assert_eq!(prefixes, vec![("cd", 2), ("dvd", 3)]);
+        assert_eq!(group_humane_by_prefix::<&str>(&[]).next(), None);
+    }
+}
@@ -0,0 +1,128 @@
+//! Helpers for checking that a comparator is a valid total order.
+//!
+//! `slice::sort_by` requires its comparator to be a total order; custom comparators that are not
+//! (for example because they are not transitive) can produce garbage orderings. The functions in
+//! this module compare every pair and triple of a set of inputs and report the first
+//! counterexample found.
+//!
+//! This module is only available with the `testing` feature.
+use std::cmp::Ordering;
+use std::fmt;
+use std::string::String;
+use std::vec::Vec;
+
+/// A violation of the total-order laws, holding the inputs that demonstrate it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum LawViolation<T> {
+    /// `compare(a, a)` is not `Equal`, or `compare(a, b)` is not `Equal` although `a == b`.
+    Equality(T, T),
+    /// `compare(a, b)` is not the reverse of `compare(b, a)`.
+    Antisymmetry(T, T),
+    /// `a <= b` and `b <= c` but not `a <= c`.
+    Transitivity(T, T, T)
+}
+
+impl<T> fmt::Display for LawViolation<T> where T: fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LawViolation::Equality(ref a, ref b) =>
+                write!(f, "{:?} and {:?} are equal but do not compare as Equal", a, b),
+            LawViolation::Antisymmetry(ref a, ref b) =>
+                write!(f, "comparing {:?} and {:?} is not antisymmetric", a, b),
+            LawViolation::Transitivity(ref a, ref b, ref c) =>
+                write!(f, "{:?} <= {:?} <= {:?} but not {:?} <= {:?}", a, b, c, a, c)
+        }
+    }
+}
+
+/// Checks the total-order laws of `compare` on all pairs and triples of `inputs`.
+///
+/// The number of comparisons is cubic in the number of inputs, a few dozen inputs are usually
+/// enough to find problems.
+///
+/// ```
+/// use humanesort::HumaneOrder;
+/// use humanesort::testing::{check_order_laws, generate_inputs};
+/// let inputs = generate_inputs(42, 40);
+/// assert_eq!(check_order_laws(&inputs, |a: &String, b: &String| a.humane_cmp(b)), Ok(()));
+/// ```
+pub fn check_order_laws<T, F>(inputs: &[T], compare: F) -> Result<(), LawViolation<T>>
+    where T: PartialEq + Clone, F: Fn(&T, &T) -> Ordering {
+    for a in inputs {
+        for b in inputs {
+            let forward = compare(a, b);
+            if a == b && forward != Ordering::Equal {
+                return Err(LawViolation::Equality(a.clone(), b.clone()))
+            }
+            if forward != compare(b, a).reverse() {
+                return Err(LawViolation::Antisymmetry(a.clone(), b.clone()))
+            }
+        }
+    }
+    for a in inputs {
+        for b in inputs {
+            if compare(a, b) == Ordering::Greater {
+                continue
+            }
+            for c in inputs {
+                if compare(b, c) != Ordering::Greater && compare(a, c) == Ordering::Greater {
+                    return Err(LawViolation::Transitivity(a.clone(), b.clone(), c.clone()))
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Generates `count` pseudo-random strings mixing the input classes that commonly trip up
+/// comparators: digit runs with leading zeros, mixed case, separators, and non-ASCII text.
+///
+/// The same `seed` always produces the same strings.
+pub fn generate_inputs(seed: u64, count: usize) -> Vec<String> {
+    const PIECES: &[&str] = &[
+        "0", "00", "007", "1", "2", "10", "9", "99999999999", "a", "A", "b", "Z", "file", "File",
+        "-", "_", ".", " ", "é", "e\u{301}", "ß", "１２", "٣", "ⅷ", "~", ""
+    ];
+    let mut state = seed ^ 0x9e37_79b9_7f4a_7c15;
+    let mut next = move || {
+        // xorshift64*
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    };
+    (0..count).map(|_| {
+        let pieces = (next() % 5) as usize;
+        (0..pieces).map(|_| PIECES[(next() % PIECES.len() as u64) as usize]).collect()
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use super::{check_order_laws, generate_inputs, LawViolation};
+
+    #[test]
+    fn detects_non_transitive_comparator() {
+        // Compares by length if the lengths differ by more than one, by content otherwise.
+        let broken = |a: &&str, b: &&str| -> Ordering {
+            if (a.len() as isize - b.len() as isize).abs() > 1 {
+                a.len().cmp(&b.len())
+            } else {
+                a.cmp(b)
+            }
+        };
+        match check_order_laws(&["b", "ab", "aaa"], broken) {
+            Err(LawViolation::Transitivity(..)) => (),
+            other => panic!("expected a transitivity violation, got {:?}", other)
+        }
+        assert_eq!(check_order_laws(&["x", "y"], |_: &&str, _: &&str| Ordering::Less),
+            Err(LawViolation::Equality("x", "x")));
+    }
+
+    #[test]
+    fn generated_inputs_are_deterministic() {
+        assert_eq!(generate_inputs(7, 20), generate_inputs(7, 20));
+        assert_eq!(generate_inputs(7, 20).len(), 20);
+    }
+}
@@ -0,0 +1,334 @@
+//! Recognition of ISO 8601 timestamps with a UTC offset, of common date formats and of durations.
+
+/// A date and time as year, month, day, hour, minute, second and nanoseconds.
+pub(crate) type DateTime = [u64; 7];
+
+/// Parses a timestamp such as "2024-05-01T10:00+02:00" at the start of `s`.
+///
+/// Seconds and fractions of a second are optional, the offset is required and is either "Z" or
+/// given as "+02:00" or "+0200". Returns the length of the timestamp and its value in UTC.
+pub(crate) fn parse(s: &str) -> Option<(usize, DateTime)> {
+    let mut parser = Parser { s: s.as_bytes(), pos: 0 };
+    let year = parser.digits(4)?;
+    parser.expect(b'-')?;
+    let month = parser.digits(2)?;
+    parser.expect(b'-')?;
+    let day = parser.digits(2)?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None
+    }
+    parser.expect(b'T').or_else(|| parser.expect(b't'))?;
+    let hour = parser.digits(2)?;
+    parser.expect(b':')?;
+    let minute = parser.digits(2)?;
+    let mut second = 0;
+    let mut nanos = 0;
+    if parser.expect(b':').is_some() {
+        second = parser.digits(2)?;
+        if parser.expect(b'.').or_else(|| parser.expect(b',')).is_some() {
+            nanos = parser.fraction()?;
+        }
+    }
+    if hour > 23 || minute > 59 || second > 60 {
+        return None
+    }
+    let offset = parser.offset()?;
+    let minutes = days_from_civil(year, month, day) * 1440 + (hour * 60 + minute) as i64 - offset;
+    let (year, month, day) = civil_from_days(minutes.div_euclid(1440));
+    if year < 0 {
+        return None
+    }
+    let minutes = minutes.rem_euclid(1440) as u64;
+    Some((parser.pos, [year as u64, month, day, minutes / 60, minutes % 60, second, nanos]))
+}
+
+/// Parses a date such as "2024-05-01", "1.5.2024" or "2-Jan-2024" at the start of `s`.
+///
+/// Dates with the day first separate it from the month by dots, or from an English month name
+/// by a dash, slash or space. ISO dates may be followed by a local time such as "T10:00" or
+/// " 10:00:30", which is taken as it is. Returns the length of the date and its fields.
+pub(crate) fn parse_date(s: &str) -> Option<(usize, DateTime)> {
+    let mut parser = Parser { s: s.as_bytes(), pos: 0 };
+    let (year, month, day, iso) = match parser.digits(4) {
+        Some(year) => {
+            parser.expect(b'-')?;
+            let month = parser.digits(2)?;
+            parser.expect(b'-')?;
+            (year, month, parser.digits(2)?, true)
+        }
+        None => {
+            let day = parser.digits(2).or_else(|| parser.digits(1))?;
+            let month = match parser.expect(b'.') {
+                Some(()) => {
+                    let month = parser.digits(2).or_else(|| parser.digits(1))?;
+                    parser.expect(b'.')?;
+                    month
+                }
+                None => {
+                    let separator = *parser.s.get(parser.pos).filter(|&&b| b == b'-' || b == b'/' || b == b' ')?;
+                    parser.pos += 1;
+                    let month = parser.month_name()?;
+                    parser.expect(separator)?;
+                    month
+                }
+            };
+            (parser.digits(4)?, month, day, false)
+        }
+    };
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) || parser.at_digit() {
+        return None
+    }
+    let mut fields = [year, month, day, 0, 0, 0, 0];
+    if iso {
+        let date_end = parser.pos;
+        match parser.local_time() {
+            Some(time) => fields[3..].copy_from_slice(&time),
+            None => parser.pos = date_end
+        }
+    }
+    Some((parser.pos, fields))
+}
+
+/// Parses a duration such as "1h30m", "90s" or "02:15:00" at the start of `s`.
+///
+/// Durations are either numbers with the units "d", "h", "min" or "m", "s" or "sec" and "ms",
+/// each unit smaller than the one before, or clock times "M:SS" and "H:MM:SS" with an optional fraction of a
+/// second. Returns the length of the duration and its value in milliseconds.
+pub(crate) fn parse_duration(s: &str) -> Option<(usize, u64)> {
+    let mut parser = Parser { s: s.as_bytes(), pos: 0 };
+    let first = parser.number()?;
+    if parser.expect(b':').is_some() {
+        let second = parser.digits(2)?;
+        let (hours, minutes, seconds) = match parser.expect(b':') {
+            Some(()) => (first, second, parser.digits(2)?),
+            None => (0, first, second)
+        };
+        let mut millis = 0;
+        let fraction_start = parser.pos;
+        if parser.expect(b'.').is_some() {
+            match parser.fraction() {
+                Some(nanos) => millis = nanos / 1_000_000,
+                None => parser.pos = fraction_start
+            }
+        }
+        if hours > 0 && minutes > 59 || seconds > 59 || parser.at_digit() {
+            return None
+        }
+        return Some((parser.pos, ((hours * 60 + minutes) * 60 + seconds) * 1000 + millis))
+    }
+    let (factor, mut previous) = parser.duration_unit()?;
+    let mut total = first * factor;
+    loop {
+        let component_start = parser.pos;
+        match parser.number().and_then(|value| Some((value, parser.duration_unit()?))) {
+            Some((value, (factor, rank))) if rank < previous => {
+                total += value * factor;
+                previous = rank;
+            }
+            _ => return Some((component_start, total))
+        }
+    }
+}
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize
+}
+
+impl<'a> Parser<'a> {
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        match self.s.get(self.pos) {
+            Some(&b) if b == byte => {
+                self.pos += 1;
+                Some(())
+            }
+            _ => None
+        }
+    }
+
+    fn digits(&mut self, count: usize) -> Option<u64> {
+        let digits = self.s.get(self.pos..self.pos + count)?;
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return None
+        }
+        self.pos += count;
+        Some(digits.iter().fold(0, |value, &d| value * 10 + u64::from(d - b'0')))
+    }
+
+    /// Parses a number of up to nine digits.
+    fn number(&mut self) -> Option<u64> {
+        let count = self.s[self.pos..].iter().take_while(|b| b.is_ascii_digit()).count();
+        match count {
+            1..=9 => self.digits(count),
+            _ => None
+        }
+    }
+
+    /// Parses the unit of a duration, returning its length in milliseconds and its rank among
+    /// the units.
+    fn duration_unit(&mut self) -> Option<(u64, u8)> {
+        let len = self.s[self.pos..].iter().take_while(|b| b.is_ascii_alphanumeric() && !b.is_ascii_digit()).count();
+        let unit = match &self.s[self.pos..self.pos + len] {
+            b"d" => (86_400_000, 4),
+            b"h" => (3_600_000, 3),
+            b"min" | b"m" => (60_000, 2),
+            b"s" | b"sec" => (1000, 1),
+            b"ms" => (1, 0),
+            _ => return None
+        };
+        self.pos += len;
+        Some(unit)
+    }
+
+    fn at_digit(&self) -> bool {
+        self.s.get(self.pos).is_some_and(u8::is_ascii_digit)
+    }
+
+    /// Parses an English month name or its abbreviation to the first three letters.
+    fn month_name(&mut self) -> Option<u64> {
+        const MONTHS: [&str; 12] = ["january", "february", "march", "april", "may", "june", "july",
+                                    "august", "september", "october", "november", "december"];
+        let len = self.s[self.pos..].iter().take_while(|b| b.is_ascii_alphabetic()).count();
+        let name = &self.s[self.pos..self.pos + len];
+        let month = MONTHS.iter().position(|month| {
+            name.eq_ignore_ascii_case(month.as_bytes()) || len == 3 && name.eq_ignore_ascii_case(&month.as_bytes()[..3])
+        })?;
+        self.pos += len;
+        Some(month as u64 + 1)
+    }
+
+    /// Parses a time of day following a date, returning the hour, minute, second and nanoseconds.
+    fn local_time(&mut self) -> Option<[u64; 4]> {
+        self.expect(b'T').or_else(|| self.expect(b't')).or_else(|| self.expect(b' '))?;
+        let hour = self.digits(2)?;
+        self.expect(b':')?;
+        let minute = self.digits(2)?;
+        let mut time = [hour, minute, 0, 0];
+        if self.expect(b':').is_some() {
+            time[2] = self.digits(2)?;
+            let fraction_start = self.pos;
+            if self.expect(b'.').or_else(|| self.expect(b',')).is_some() {
+                match self.fraction() {
+                    Some(nanos) => time[3] = nanos,
+                    None => self.pos = fraction_start
+                }
+            }
+        }
+        match hour > 23 || minute > 59 || time[2] > 60 || self.at_digit() {
+            true => None,
+            false => Some(time)
+        }
+    }
+
+    /// Parses the digits of a fraction of a second as nanoseconds, ignoring digits beyond them.
+    fn fraction(&mut self) -> Option<u64> {
+        let count = self.s[self.pos..].iter().take_while(|b| b.is_ascii_digit()).count();
+        if count == 0 {
+            return None
+        }
+        let digits = &self.s[self.pos..self.pos + count];
+        self.pos += count;
+        Some((0..9).fold(0, |value, i| value * 10 + digits.get(i).map_or(0, |&d| u64::from(d - b'0'))))
+    }
+
+    /// Parses the offset from UTC in minutes.
+    fn offset(&mut self) -> Option<i64> {
+        if self.expect(b'Z').or_else(|| self.expect(b'z')).is_some() {
+            return Some(0)
+        }
+        let sign = match self.s.get(self.pos) {
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return None
+        };
+        self.pos += 1;
+        let hours = self.digits(2)?;
+        let _ = self.expect(b':');
+        let minutes = self.digits(2)?;
+        if hours > 23 || minutes > 59 {
+            return None
+        }
+        Some(sign * (hours * 60 + minutes) as i64)
+    }
+}
+
+fn is_leap_year(year: u64) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+/// Returns the number of days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: u64, month: u64, day: u64) -> i64 {
+    let year = year as i64 - if month <= 2 { 1 } else { 0 };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Returns the date that is `days` days after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u64, u64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u64;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u64;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, parse_date, parse_duration};
+
+    #[test]
+    fn normalizes_to_utc() {
+        assert_eq!(parse("2024-05-01T10:00+02:00.log"), Some((22, [2024, 5, 1, 8, 0, 0, 0])));
+        assert_eq!(parse("2024-03-01T00:30:15.25+0100"), Some((27, [2024, 2, 29, 23, 30, 15, 250_000_000])));
+        assert_eq!(parse("1999-12-31T23:00-01:30"), Some((22, [2000, 1, 1, 0, 30, 0, 0])));
+        assert_eq!(parse("2024-05-01T10:00Z"), Some((17, [2024, 5, 1, 10, 0, 0, 0])));
+        assert_eq!(parse("2024-05-01T10:00"), None);
+        assert_eq!(parse("2023-02-29T10:00Z"), None);
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(parse_date("2024-05-01.log"), Some((10, [2024, 5, 1, 0, 0, 0, 0])));
+        assert_eq!(parse_date("2024-05-01 10:30:15.5 x"), Some((21, [2024, 5, 1, 10, 30, 15, 500_000_000])));
+        assert_eq!(parse_date("2024-05-01T10"), Some((10, [2024, 5, 1, 0, 0, 0, 0])));
+        assert_eq!(parse_date("1.5.2024"), Some((8, [2024, 5, 1, 0, 0, 0, 0])));
+        assert_eq!(parse_date("29.02.2023"), None);
+        assert_eq!(parse_date("2-Jan-2024"), Some((10, [2024, 1, 2, 0, 0, 0, 0])));
+        assert_eq!(parse_date("15 december 2023"), Some((16, [2023, 12, 15, 0, 0, 0, 0])));
+        assert_eq!(parse_date("15 Decem 2023"), None);
+        assert_eq!(parse_date("2-Jan/2024"), None);
+        assert_eq!(parse_date("1.5.20245"), None);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("1h30m.mp4"), Some((5, 5_400_000)));
+        assert_eq!(parse_duration("90min"), Some((5, 5_400_000)));
+        assert_eq!(parse_duration("2s500ms"), Some((7, 2500)));
+        assert_eq!(parse_duration("02:15:00"), Some((8, 8_100_000)));
+        assert_eq!(parse_duration("3:05.25 take"), Some((7, 185_250)));
+        assert_eq!(parse_duration("1h30"), Some((2, 3_600_000)));
+        assert_eq!(parse_duration("30m1h"), Some((3, 1_800_000)));
+        assert_eq!(parse_duration("5mins"), None);
+        assert_eq!(parse_duration("1:75"), None);
+        assert_eq!(parse_duration("7"), None);
+    }
+}
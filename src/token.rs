@@ -0,0 +1,894 @@
+//! Token streams adjusted according to `SortOptions`.
+use std::borrow::Cow;
+use std::boxed::Box;
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::mem;
+use std::slice;
+use std::string::{String, ToString};
+use std::vec::Vec;
+#[cfg(feature = "any_ascii")]
+use any_ascii;
+#[cfg(feature = "native-collation")]
+use ::native;
+use timestamp;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+use ::{compare_numbers, numeric_digits, options, CaseOrder, HexNumbers, NegativeNumbers, NumberOrder, SortOptions, SortingType, TokenIterator, Whitespace, ZeroOrder};
+
+/// Separators recognized between the bounds of a range.
+const RANGE_SEPARATORS: &[&str] = &["-", "\u{2013}", "\u{2014}"];
+
+/// A token as compared under non-default options.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    /// The value of a number as ASCII digits.
+    Number(Cow<'a, str>),
+    /// The integer part and the digits of the fraction of a decimal number such as "1.25".
+    Decimal(Cow<'a, str>, Cow<'a, str>),
+    /// The start and end values of a range such as "10-12".
+    Range([Cow<'a, str>; 2]),
+    /// The date and time of a timestamp in UTC or of a date, from the year down to nanoseconds.
+    Instant(Vec<Cow<'a, str>>),
+    /// The levels of an outline number such as "1.2.a", numbers as ASCII digits and single letters.
+    Outline(Vec<Cow<'a, str>>),
+    /// A number preceded by a minus sign.
+    Negative(Box<Token<'a>>)
+}
+
+impl<'a> Token<'a> {
+    /// The numeric values of the token, a number is compared as a range with a single value.
+    fn numbers(&self) -> &[Cow<'a, str>] {
+        match *self {
+            Token::Text(_) | Token::Negative(_) => &[],
+            Token::Number(ref value) | Token::Decimal(ref value, _) => slice::from_ref(value),
+            Token::Range(ref bounds) => bounds,
+            Token::Instant(ref fields) | Token::Outline(ref fields) => fields
+        }
+    }
+
+    /// The digits of the fraction of a decimal number without trailing zeros.
+    fn fraction(&self) -> &str {
+        match *self {
+            Token::Decimal(_, ref fraction) => fraction.trim_end_matches('0'),
+            _ => ""
+        }
+    }
+}
+
+/// Compares two tokens, numbers sort before or after text as set by the number order.
+pub(crate) fn compare(ours: &Token, theirs: &Token, options: &SortOptions) -> Ordering {
+    let text_first = options.number_order == NumberOrder::Last;
+    match (ours, theirs) {
+        (&Token::Text(a), &Token::Text(b)) => compare_text(a, b, options),
+        (&Token::Text(_), _) if text_first => Ordering::Less,
+        (&Token::Text(_), _) => Ordering::Greater,
+        (_, &Token::Text(_)) if text_first => Ordering::Greater,
+        (_, &Token::Text(_)) => Ordering::Less,
+        (Token::Negative(a), Token::Negative(b)) => compare(b, a, options),
+        (&Token::Negative(_), _) => Ordering::Less,
+        (_, &Token::Negative(_)) => Ordering::Greater,
+        (&Token::Outline(_), _) | (_, &Token::Outline(_)) => compare_levels(ours.numbers(), theirs.numbers()),
+        _ => {
+            let values = ours.numbers().iter().zip(theirs.numbers());
+            values.map(|(a, b)| compare_numbers(a, b)).find(|cmp| *cmp != Ordering::Equal)
+                .unwrap_or_else(|| ours.numbers().len().cmp(&theirs.numbers().len()))
+                // Fractions without trailing zeros compare like their digits.
+                .then_with(|| ours.fraction().cmp(theirs.fraction()))
+        }
+    }
+}
+
+/// Compares the levels of outline numbers, numbers before letters and parents before their
+/// children. Other numbers compare like outlines with a single level.
+fn compare_levels(ours: &[Cow<str>], theirs: &[Cow<str>]) -> Ordering {
+    let is_number = |level: &str| level.bytes().all(|b| b.is_ascii_digit());
+    let levels = ours.iter().zip(theirs).map(|(a, b)| match (is_number(a), is_number(b)) {
+        (true, true) => compare_numbers(a, b),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.cmp(b)
+    });
+    levels.fold(Ordering::Equal, Ordering::then).then_with(|| ours.len().cmp(&theirs.len()))
+}
+
+/// Breaks a tie between token streams that are equal, by the leading zeros of their numbers.
+pub(crate) fn compare_zeros<'a, I, J>(ours: I, theirs: J, order: ZeroOrder) -> Ordering
+    where I: Iterator<Item = Token<'a>>, J: Iterator<Item = Token<'a>> {
+    let ours = ours.flat_map(|token| leading_zeros(&token));
+    let theirs = theirs.flat_map(|token| leading_zeros(&token));
+    match order {
+        ZeroOrder::Ignore => Ordering::Equal,
+        ZeroOrder::FewerFirst => ours.cmp(theirs),
+        ZeroOrder::MoreFirst => theirs.cmp(ours)
+    }
+}
+
+/// Returns the number of leading zeros of each number of a token.
+fn leading_zeros(token: &Token) -> Vec<usize> {
+    match *token {
+        Token::Negative(ref number) => leading_zeros(number),
+        _ => token.numbers().iter().map(|n| n.len() - n.trim_start_matches('0').len()).collect()
+    }
+}
+
+fn compare_text(ours: &str, theirs: &str, options: &SortOptions) -> Ordering {
+    let (ours, theirs) = (&*normalize_text(ours, options), &*normalize_text(theirs, options));
+    // Transliterations can be equal for different text, which is then compared as usual.
+    #[cfg(feature = "any_ascii")]
+    {
+        if options.transliterate {
+            let cmp = compare_text_by_case(&transliterate(ours), &transliterate(theirs), options.case_order);
+            if cmp != Ordering::Equal {
+                return cmp
+            }
+        }
+    }
+    #[cfg(feature = "icu")]
+    {
+        if let Some(ref collation) = options.collation {
+            let cmp = collation.compare(ours, theirs);
+            if cmp != Ordering::Equal {
+                return cmp
+            }
+        }
+    }
+    #[cfg(feature = "native-collation")]
+    {
+        if options.native_collation {
+            if let Some(cmp) = native::compare(ours, theirs) {
+                return cmp
+            }
+        }
+    }
+    compare_text_by_case(ours, theirs, options.case_order)
+}
+
+/// Applies the whitespace treatment and removes diacritics and punctuation if requested.
+fn normalize_text<'a>(text: &'a str, options: &SortOptions) -> Cow<'a, str> {
+    let text = match options.ignore_diacritics && !text.is_ascii() {
+        true => Cow::Owned(text.nfd().filter(|&c| !is_combining_mark(c)).collect()),
+        false => Cow::Borrowed(text)
+    };
+    let whitespace = options.whitespace;
+    let skipped = |c: char| whitespace != Whitespace::Keep && c.is_whitespace() || options.ignore_punctuation && is_punctuation(c);
+    if !text.contains(skipped) {
+        return text
+    }
+    let mut normalized = String::with_capacity(text.len());
+    let mut after_space = false;
+    for c in text.chars().filter(|&c| !(options.ignore_punctuation && is_punctuation(c))) {
+        match (c.is_whitespace(), whitespace) {
+            (false, _) | (true, Whitespace::Keep) => normalized.push(c),
+            (true, Whitespace::Collapse) if !after_space => normalized.push(' '),
+            _ => {}
+        }
+        after_space = c.is_whitespace();
+    }
+    Cow::Owned(normalized)
+}
+
+/// Whether `c` is ASCII punctuation or a symbol, or a common non-ASCII punctuation mark such as
+/// dashes, quotation marks and CJK brackets.
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || matches!(c, '¡' | '«' | '·' | '»' | '¿' | '\u{2010}'..='\u{2027}' | '\u{2030}'..='\u{205E}'
+                                               | '\u{3001}'..='\u{3003}' | '\u{3008}'..='\u{3011}')
+}
+
+#[cfg(feature = "any_ascii")]
+fn transliterate(text: &str) -> Cow<'_, str> {
+    match text.is_ascii() {
+        true => Cow::Borrowed(text),
+        false => Cow::Owned(any_ascii::any_ascii(text))
+    }
+}
+
+fn compare_text_by_case(ours: &str, theirs: &str, case_order: CaseOrder) -> Ordering {
+    match case_order {
+        CaseOrder::CodePoint => ours.cmp(theirs),
+        _ => options::compare_folded(ours, theirs)
+    }
+}
+
+/// Tokens of a string, reclassified and merged as requested by the options.
+pub(crate) struct Tokens<'a> {
+    string: &'a str,
+    inner: Peekable<TokenIterator<'a, SortingType>>,
+    dates: bool,
+    durations: bool,
+    decimal_separator: char,
+    decimals: bool,
+    delimited_digits: bool,
+    hex_numbers: HexNumbers,
+    ip_addresses: bool,
+    max_digits: usize,
+    negative_numbers: NegativeNumbers,
+    outlines: bool,
+    ranges: bool,
+    scientific: bool,
+    sizes: bool,
+    thousands_separator: Option<char>,
+    timestamps: bool,
+    /// Whether the text before the next number ended with a minus sign.
+    negative: bool
+}
+
+impl<'a> Tokens<'a> {
+    pub(crate) fn new(s: &'a str, options: &SortOptions) -> Self {
+        Tokens {
+            string: s,
+            inner: TokenIterator::digit_runs(s).peekable(),
+            dates: options.dates,
+            durations: options.durations,
+            decimal_separator: options.decimal_separator.unwrap_or('.'),
+            decimals: options.decimals,
+            delimited_digits: options.delimited_digits,
+            hex_numbers: options.hex_numbers,
+            ip_addresses: options.ip_addresses,
+            max_digits: options.limits.max_digits.unwrap_or(usize::MAX),
+            negative_numbers: options.negative_numbers,
+            outlines: options.outlines,
+            ranges: options.ranges,
+            scientific: options.scientific,
+            sizes: options.sizes,
+            thousands_separator: options.thousands_separator,
+            timestamps: options.timestamps,
+            negative: false
+        }
+    }
+
+    fn offset(&self, token: &str) -> usize {
+        token.as_ptr() as usize - self.string.as_ptr() as usize
+    }
+
+    /// Returns the effective type of a token produced by the inner tokenizer.
+    fn classify(&self, token: &str, kind: SortingType) -> SortingType {
+        if kind == SortingType::Numeric && token.len() > self.max_digits {
+            return SortingType::NonNumeric
+        }
+        if kind == SortingType::Numeric && self.delimited_digits {
+            let start = self.offset(token);
+            let end = start + token.len();
+            let letter_before = self.string[..start].chars().next_back().is_some_and(char::is_alphabetic);
+            let letter_after = self.string[end..].chars().next().is_some_and(char::is_alphabetic);
+            if letter_before || letter_after {
+                return SortingType::NonNumeric
+            }
+        }
+        kind
+    }
+
+    /// Consumes a timestamp or date starting with `token` as recognized by `parse`, returning
+    /// its fields.
+    fn instant(&mut self, token: &str, parse: fn(&str) -> Option<(usize, timestamp::DateTime)>) -> Option<Token<'a>> {
+        let start = self.offset(token);
+        let (len, fields) = parse(&self.string[start..])?;
+        // The timestamp can end within a token, tokenizing starts over after it.
+        self.inner = TokenIterator::digit_runs(&self.string[start + len..]).peekable();
+        Some(Token::Instant(fields.iter().map(|field| Cow::Owned(field.to_string())).collect()))
+    }
+
+    /// Consumes a hexadecimal number starting with `token`, returning its value.
+    fn hex(&mut self, token: &str) -> Option<Token<'a>> {
+        let start = self.offset(token);
+        let rest = &self.string[start..];
+        let prefixed = token == "0" && (rest.starts_with("0x") || rest.starts_with("0X"));
+        let digits_start = match prefixed {
+            true => 2,
+            false => 0
+        };
+        let len = rest[digits_start..].bytes().take_while(u8::is_ascii_hexdigit).count();
+        let bare = self.hex_numbers == HexNumbers::Bare && len >= token.len()
+            && !self.string[..start].chars().next_back().is_some_and(char::is_alphanumeric)
+            && !rest[len..].chars().next().is_some_and(char::is_alphanumeric);
+        let end = match (prefixed && len > 0, bare) {
+            (true, _) => digits_start + len,
+            (false, true) => len,
+            (false, false) => return None
+        };
+        self.inner = TokenIterator::digit_runs(&self.string[start + end..]).peekable();
+        Some(Token::Number(Cow::Owned(hex_to_decimal(&rest[digits_start..end]))))
+    }
+
+    /// Returns whether the text ending at `end` ends with a minus sign of the number after it.
+    fn sign_before_number(&mut self, end: usize) -> bool {
+        if self.negative_numbers == NegativeNumbers::Never || !self.string[..end].ends_with('-') {
+            return false
+        }
+        let number_follows = match self.inner.peek() {
+            Some(&(next, next_kind)) => self.classify(next, next_kind) == SortingType::Numeric,
+            None => false
+        };
+        let delimited = !self.string[..end - 1].chars().next_back().is_some_and(char::is_alphanumeric);
+        number_follows && (delimited || self.negative_numbers == NegativeNumbers::Always)
+    }
+
+    /// Builds the token of a number, consuming what follows it if it forms a larger token.
+    fn number(&mut self, token: &'a str) -> Token<'a> {
+        if self.timestamps && token.len() == 4 {
+            if let Some(instant) = self.instant(token, timestamp::parse) {
+                return instant
+            }
+        }
+        if self.dates && token.len() <= 4 {
+            if let Some(date) = self.instant(token, timestamp::parse_date) {
+                return date
+            }
+        }
+        if self.ip_addresses {
+            let start = self.offset(token);
+            let after_number = self.string[..start].ends_with('.')
+                && self.string[..start - 1].chars().next_back().is_some_and(|c| c.is_ascii_digit());
+            if let Some((len, address)) = parse_ipv4(&self.string[start..]).filter(|_| !after_number) {
+                self.inner = TokenIterator::digit_runs(&self.string[start + len..]).peekable();
+                return Token::Number(Cow::Owned(address.to_string()))
+            }
+        }
+        if self.hex_numbers != HexNumbers::Never {
+            if let Some(hex) = self.hex(token) {
+                return hex
+            }
+        }
+        if self.scientific {
+            if let Some(scientific) = self.scientific(token) {
+                return scientific
+            }
+        }
+        if self.sizes {
+            if let Some(size) = self.size(token) {
+                return size
+            }
+        }
+        if self.durations {
+            let start = self.offset(token);
+            if let Some((len, millis)) = timestamp::parse_duration(&self.string[start..]) {
+                self.inner = TokenIterator::digit_runs(&self.string[start + len..]).peekable();
+                return Token::Number(Cow::Owned(millis.to_string()))
+            }
+        }
+        if self.outlines {
+            if let Some(outline) = self.outline(token) {
+                return outline
+            }
+        }
+        let value = match self.thousands_separator {
+            Some(separator) => self.grouped(token, separator),
+            None => numeric_digits(token)
+        };
+        if self.decimals {
+            if let Some(fraction) = self.fraction() {
+                return Token::Decimal(value, Cow::Borrowed(fraction))
+            }
+        }
+        if self.ranges {
+            if let Some(end) = self.range_end() {
+                return Token::Range([numeric_digits(token), numeric_digits(end)])
+            }
+        }
+        Token::Number(value)
+    }
+
+    /// Returns the value of a number with thousands separators starting with `token`, consuming
+    /// the groups of three digits that follow it.
+    fn grouped(&mut self, token: &'a str, separator: char) -> Cow<'a, str> {
+        let mut value = numeric_digits(token);
+        if value.len() > 3 {
+            return value
+        }
+        loop {
+            let mut ahead = self.inner.clone();
+            match ahead.next() {
+                Some((text, SortingType::NonNumeric)) if text.chars().eq(Some(separator)) => {},
+                _ => return value
+            }
+            match ahead.next() {
+                Some((digits, kind)) if self.classify(digits, kind) == SortingType::Numeric
+                    && digits.len() == 3 && digits.bytes().all(|b| b.is_ascii_digit()) => {
+                    value.to_mut().push_str(digits);
+                    self.inner = ahead;
+                }
+                _ => return value
+            }
+        }
+    }
+
+    /// Consumes the fraction and exponent of a number in scientific notation starting with
+    /// `token`, returning its value as a decimal number.
+    fn scientific(&mut self, token: &'a str) -> Option<Token<'a>> {
+        let is_digits = |digits: &str, kind| kind == SortingType::Numeric && digits.bytes().all(|b| b.is_ascii_digit());
+        let mut ahead = self.inner.clone();
+        let mut fraction = "";
+        let mut after_point = ahead.clone();
+        if let (Some((point, SortingType::NonNumeric)), Some((digits, kind))) = (after_point.next(), after_point.next()) {
+            if point.chars().eq(Some(self.decimal_separator)) && self.classify(digits, kind) == SortingType::Numeric
+                && is_digits(digits, kind) {
+                fraction = digits;
+                ahead = after_point;
+            }
+        }
+        let negative = match ahead.next() {
+            Some(("e", SortingType::NonNumeric)) | Some(("E", SortingType::NonNumeric)) => false,
+            Some(("e+", SortingType::NonNumeric)) | Some(("E+", SortingType::NonNumeric)) => false,
+            Some(("e-", SortingType::NonNumeric)) | Some(("E-", SortingType::NonNumeric)) => true,
+            _ => return None
+        };
+        let exponent = match ahead.next() {
+            Some((digits, kind)) if digits.len() <= 3 && is_digits(digits, kind) => digits.parse::<usize>().ok()?,
+            _ => return None
+        };
+        self.inner = ahead;
+        let integer = numeric_digits(token);
+        let digits = integer.to_string() + fraction;
+        // The position of the decimal point in `digits`.
+        let point = match negative {
+            true => integer.len() as isize - exponent as isize,
+            false => (integer.len() + exponent) as isize
+        };
+        let (integer, fraction) = match point {
+            _ if point <= 0 => ("0".to_string(), "0".repeat(point.unsigned_abs()) + &digits),
+            _ if point as usize >= digits.len() => (digits.clone() + &"0".repeat(point as usize - digits.len()), String::new()),
+            _ => (digits[..point as usize].to_string(), digits[point as usize..].to_string())
+        };
+        Some(Token::Decimal(Cow::Owned(integer), Cow::Owned(fraction)))
+    }
+
+    /// Consumes the fraction and the unit of a data size starting with `token`, returning the
+    /// number of bytes as a decimal number.
+    fn size(&mut self, token: &'a str) -> Option<Token<'a>> {
+        let end = self.offset(token) + token.len();
+        let rest = &self.string[end..];
+        let fraction = match rest.strip_prefix(self.decimal_separator) {
+            Some(after) => &after[..after.bytes().take_while(u8::is_ascii_digit).count()],
+            None => ""
+        };
+        let rest = match fraction.is_empty() {
+            true => rest,
+            false => &rest[self.decimal_separator.len_utf8() + fraction.len()..]
+        };
+        let (spaced, unit) = match rest.strip_prefix(' ') {
+            Some(unit) => (true, unit),
+            None => (false, rest)
+        };
+        let (base, power, len) = size_unit(unit)?;
+        if spaced && !unit[..len].ends_with('B') || unit[len..].chars().next().is_some_and(char::is_alphanumeric) {
+            return None
+        }
+        let end = self.string.len() - unit.len() + len;
+        self.inner = TokenIterator::digit_runs(&self.string[end..]).peekable();
+        let mut bytes = numeric_digits(token).into_owned() + fraction;
+        for _ in 0..power {
+            bytes = multiply(&bytes, base);
+        }
+        let point = bytes.len() - fraction.len();
+        Some(Token::Decimal(Cow::Owned(bytes[..point].to_string()), Cow::Owned(bytes[point..].to_string())))
+    }
+
+    /// Consumes the levels of an outline number starting with `token`, which follow it after
+    /// dots.
+    fn outline(&mut self, token: &'a str) -> Option<Token<'a>> {
+        let start = self.offset(token);
+        let s = &self.string[start..];
+        let mut levels = vec![numeric_digits(token)];
+        let mut end = token.len();
+        while let Some(rest) = s[end..].strip_prefix('.') {
+            let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+            let letters = rest.bytes().take_while(u8::is_ascii_alphabetic).count();
+            let len = match (digits, letters) {
+                (0, 1) => 1,
+                (0, _) => break,
+                _ => digits
+            };
+            levels.push(Cow::Borrowed(&rest[..len]));
+            end += 1 + len;
+        }
+        if levels.len() < 2 {
+            return None
+        }
+        self.inner = TokenIterator::digit_runs(&self.string[start + end..]).peekable();
+        Some(Token::Outline(levels))
+    }
+
+    /// Consumes a decimal separator and the digits following a number, returning the digits.
+    fn fraction(&mut self) -> Option<&'a str> {
+        let mut ahead = self.inner.clone();
+        match ahead.next() {
+            Some((point, SortingType::NonNumeric)) if point.chars().eq(Some(self.decimal_separator)) => {},
+            _ => return None
+        }
+        match ahead.next() {
+            Some((digits, kind)) if self.classify(digits, kind) == SortingType::Numeric
+                && digits.bytes().all(|b| b.is_ascii_digit()) => {
+                self.inner = ahead;
+                Some(digits)
+            }
+            _ => None
+        }
+    }
+
+    /// Consumes a separator and a number following a number, returning the number.
+    fn range_end(&mut self) -> Option<&'a str> {
+        let mut ahead = self.inner.clone();
+        match ahead.next() {
+            Some((separator, SortingType::NonNumeric)) if RANGE_SEPARATORS.contains(&separator) => {},
+            _ => return None
+        }
+        match ahead.next() {
+            Some((end, kind)) if self.classify(end, kind) == SortingType::Numeric => {
+                self.inner = ahead;
+                Some(end)
+            }
+            _ => None
+        }
+    }
+}
+
+/// Parses an IPv4 address in dotted-quad notation at the start of `s`, returning its length and
+/// its value.
+///
+/// Octets have one to three digits, the address must not be followed by another dot and digit.
+fn parse_ipv4(s: &str) -> Option<(usize, u32)> {
+    let bytes = s.as_bytes();
+    let (mut pos, mut address) = (0, 0u32);
+    for octet in 0..4 {
+        if octet > 0 {
+            match bytes.get(pos) {
+                Some(b'.') => pos += 1,
+                _ => return None
+            }
+        }
+        let len = bytes[pos..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let value = match len {
+            1..=3 => s[pos..pos + len].parse::<u32>().ok().filter(|&value| value <= 255)?,
+            _ => return None
+        };
+        address = address << 8 | value;
+        pos += len;
+    }
+    match bytes.get(pos..pos + 2) {
+        Some(&[b'.', digit]) if digit.is_ascii_digit() => None,
+        _ => Some((pos, address))
+    }
+}
+
+/// Parses the data size unit at the start of `unit`, returning the base and power of its factor
+/// and its length.
+///
+/// Units are "B" and the decimal ("kB", "MB") and binary ("KiB", "MiB") multiples up to exabytes,
+/// the "B" can be left out of multiples ("4K", "2.5G").
+fn size_unit(unit: &str) -> Option<(u32, u32, usize)> {
+    let power = match unit.chars().next()? {
+        'B' => return Some((1000, 0, 1)),
+        'k' | 'K' => 1,
+        'M' => 2,
+        'G' => 3,
+        'T' => 4,
+        'P' => 5,
+        'E' => 6,
+        _ => return None
+    };
+    let binary = unit[1..].starts_with('i');
+    let (base, len) = match binary {
+        true => (1024, 2),
+        false => (1000, 1)
+    };
+    match unit[len..].starts_with('B') {
+        true => Some((base, power, len + 1)),
+        false => Some((base, power, len))
+    }
+}
+
+/// Multiplies a number given as decimal digits by `factor`, keeping the leading zeros.
+fn multiply(digits: &str, factor: u32) -> String {
+    let mut product: Vec<u8> = Vec::with_capacity(digits.len() + 4);
+    let mut carry = 0;
+    for digit in digits.bytes().rev() {
+        let value = u32::from(digit - b'0') * factor + carry;
+        product.push(b'0' + (value % 10) as u8);
+        carry = value / 10;
+    }
+    while carry > 0 {
+        product.push(b'0' + (carry % 10) as u8);
+        carry /= 10;
+    }
+    product.iter().rev().map(|&d| char::from(d)).collect()
+}
+
+/// Converts hexadecimal digits to decimal digits, keeping the leading zeros.
+fn hex_to_decimal(hex: &str) -> String {
+    let significant = hex.trim_start_matches('0');
+    // Decimal digits of the value, the least significant first.
+    let mut digits: Vec<u32> = Vec::new();
+    for c in significant.chars() {
+        let mut carry = c.to_digit(16).unwrap_or(0);
+        for digit in &mut digits {
+            let value = *digit * 16 + carry;
+            *digit = value % 10;
+            carry = value / 10;
+        }
+        while carry > 0 {
+            digits.push(carry % 10);
+            carry /= 10;
+        }
+    }
+    let zeros = "0".repeat(hex.len() - significant.len());
+    zeros + &digits.iter().rev().map(|&d| char::from(b'0' + d as u8)).collect::<String>()
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let (token, kind) = self.inner.next()?;
+        let kind = self.classify(token, kind);
+        if kind == SortingType::Numeric {
+            let number = self.number(token);
+            return Some(match mem::replace(&mut self.negative, false) {
+                true => Token::Negative(Box::new(number)),
+                false => number
+            })
+        }
+        // Reclassified digits are merged with the surrounding text into a single token.
+        let start = self.offset(token);
+        let mut end = start + token.len();
+        while let Some(&(next, next_kind)) = self.inner.peek() {
+            if self.classify(next, next_kind) != SortingType::NonNumeric {
+                break
+            }
+            end = self.offset(next) + next.len();
+            self.inner.next();
+        }
+        if self.sign_before_number(end) {
+            self.negative = true;
+            end -= 1;
+            if start == end {
+                return self.next()
+            }
+        }
+        Some(Token::Text(&self.string[start..end]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::boxed::Box;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+    use ::SortOptions;
+    use super::{normalize_text, parse_ipv4, Token, Tokens};
+
+    #[test]
+    fn delimited_digits() {
+        let options = SortOptions::new().delimited_digits(true);
+        let tokens: Vec<_> = Tokens::new("song.mp3 track 3-x264", &options).collect();
+        assert_eq!(tokens, vec![
+            Token::Text("song.mp3 track "),
+            Token::Number(Cow::Borrowed("3")),
+            Token::Text("-x264"),
+        ]);
+        let plain: Vec<_> = Tokens::new("mp3", &SortOptions::new()).collect();
+        assert_eq!(plain, vec![Token::Text("mp"), Token::Number(Cow::Borrowed("3"))]);
+    }
+
+    #[test]
+    fn decimals() {
+        let options = SortOptions::new().decimals(true);
+        let tokens: Vec<_> = Tokens::new("v1.25.x 3.", &options).collect();
+        assert_eq!(tokens, vec![
+            Token::Text("v"),
+            Token::Decimal(Cow::Borrowed("1"), Cow::Borrowed("25")),
+            Token::Text(".x "),
+            Token::Number(Cow::Borrowed("3")),
+            Token::Text("."),
+        ]);
+    }
+
+    #[test]
+    fn negative_numbers() {
+        use NegativeNumbers;
+        let negative = |number| Token::Negative(Box::new(Token::Number(Cow::Borrowed(number))));
+        let options = SortOptions::new().negative_numbers(NegativeNumbers::Delimited);
+        let tokens: Vec<_> = Tokens::new("-4 temp_-5 track-6", &options).collect();
+        assert_eq!(tokens, vec![
+            negative("4"),
+            Token::Text(" temp_"),
+            negative("5"),
+            Token::Text(" track-"),
+            Token::Number(Cow::Borrowed("6")),
+        ]);
+        let options = options.negative_numbers(NegativeNumbers::Always);
+        let tokens: Vec<_> = Tokens::new("track-6-", &options).collect();
+        assert_eq!(tokens, vec![Token::Text("track"), negative("6"), Token::Text("-")]);
+    }
+
+    #[test]
+    fn ranges() {
+        let options = SortOptions::new().ranges(true);
+        let tokens: Vec<_> = Tokens::new("pages 10\u{2013}12, 3-", &options).collect();
+        assert_eq!(tokens, vec![
+            Token::Text("pages "),
+            Token::Range([Cow::Borrowed("10"), Cow::Borrowed("12")]),
+            Token::Text(", "),
+            Token::Number(Cow::Borrowed("3")),
+            Token::Text("-"),
+        ]);
+        let delimited = options.delimited_digits(true);
+        let tokens: Vec<_> = Tokens::new("1-2x", &delimited).collect();
+        assert_eq!(tokens, vec![Token::Number(Cow::Borrowed("1")), Token::Text("-2x")]);
+    }
+
+    #[test]
+    fn timestamps() {
+        let options = SortOptions::new().timestamps(true);
+        let tokens: Vec<_> = Tokens::new("app-2024-05-01T10:00+02:00Z.log", &options).collect();
+        let fields = ["2024", "5", "1", "8", "0", "0", "0"].iter().map(|f| Cow::Borrowed(*f)).collect();
+        assert_eq!(tokens, vec![Token::Text("app-"), Token::Instant(fields), Token::Text("Z.log")]);
+    }
+
+    #[test]
+    fn dates() {
+        let options = SortOptions::new().dates(true);
+        let tokens: Vec<_> = Tokens::new("v2 15-Dec-2023.pdf", &options).collect();
+        let fields = ["2023", "12", "15", "0", "0", "0", "0"].iter().map(|f| Cow::Borrowed(*f)).collect();
+        assert_eq!(tokens, vec![
+            Token::Text("v"),
+            Token::Number(Cow::Borrowed("2")),
+            Token::Text(" "),
+            Token::Instant(fields),
+            Token::Text(".pdf"),
+        ]);
+    }
+
+    #[test]
+    fn hex_numbers() {
+        use HexNumbers;
+        let number = |digits| Token::Number(Cow::Owned(String::from(digits)));
+        let options = SortOptions::new().hex_numbers(HexNumbers::Prefixed);
+        let tokens: Vec<_> = Tokens::new("0x0aF 0x 0A", &options).collect();
+        assert_eq!(tokens, vec![
+            number("0175"),
+            Token::Text(" "),
+            Token::Number(Cow::Borrowed("0")),
+            Token::Text("x "),
+            Token::Number(Cow::Borrowed("0")),
+            Token::Text("A"),
+        ]);
+        let options = options.hex_numbers(HexNumbers::Bare);
+        let tokens: Vec<_> = Tokens::new("0A_7ffe_f1_1g", &options).collect();
+        assert_eq!(tokens, vec![
+            number("010"),
+            Token::Text("_"),
+            number("32766"),
+            Token::Text("_f"),
+            Token::Number(Cow::Borrowed("1")),
+            Token::Text("_"),
+            Token::Number(Cow::Borrowed("1")),
+            Token::Text("g"),
+        ]);
+        assert_eq!(super::hex_to_decimal("ffffffffffffffffffff"), "1208925819614629174706175");
+    }
+
+    #[test]
+    fn normalized_text() {
+        use ::Whitespace;
+        let normalized = |text, options| normalize_text(text, &options).into_owned();
+        assert_eq!(normalized("a \t b\u{A0}", SortOptions::new().whitespace(Whitespace::Collapse)), "a b ");
+        assert_eq!(normalized("a \t b\u{A0}", SortOptions::new().whitespace(Whitespace::Ignore)), "ab");
+        assert_eq!(normalized("a \t b", SortOptions::new()), "a \t b");
+        let punctuation = SortOptions::new().ignore_punctuation(true);
+        assert_eq!(normalized("«read_me» — v.", punctuation.clone()), "readme  v");
+        assert_eq!(normalized("a - b", punctuation.whitespace(Whitespace::Collapse)), "a b");
+        let diacritics = SortOptions::new().ignore_diacritics(true);
+        assert_eq!(normalized("Ångström, cafe\u{301}, ø", diacritics), "Angstrom, cafe, ø");
+    }
+
+    #[test]
+    fn thousands_separators() {
+        let options = SortOptions::new().thousands_separator(',').decimals(true);
+        let tokens: Vec<_> = Tokens::new("1,000,000.5 1,00 12345,678", &options).collect();
+        assert_eq!(tokens, vec![
+            Token::Decimal(Cow::Owned("1000000".to_string()), Cow::Borrowed("5")),
+            Token::Text(" "),
+            Token::Number(Cow::Borrowed("1")),
+            Token::Text(","),
+            Token::Number(Cow::Borrowed("00")),
+            Token::Text(" "),
+            Token::Number(Cow::Borrowed("12345")),
+            Token::Text(","),
+            Token::Number(Cow::Borrowed("678")),
+        ]);
+    }
+
+    #[test]
+    fn scientific() {
+        let options = SortOptions::new().scientific(true);
+        let decimal = |integer: &str, fraction: &str| Token::Decimal(Cow::Owned(integer.to_string()), Cow::Owned(fraction.to_string()));
+        let tokens: Vec<_> = Tokens::new("1e3 2.5E-4 12.5e+1 3.5 5e 7e1000", &options).collect();
+        assert_eq!(tokens, vec![
+            decimal("1000", ""),
+            Token::Text(" "),
+            decimal("0", "00025"),
+            Token::Text(" "),
+            decimal("125", ""),
+            Token::Text(" "),
+            Token::Number(Cow::Borrowed("3")),
+            Token::Text("."),
+            Token::Number(Cow::Borrowed("5")),
+            Token::Text(" "),
+            Token::Number(Cow::Borrowed("5")),
+            Token::Text("e "),
+            Token::Number(Cow::Borrowed("7")),
+            Token::Text("e"),
+            Token::Number(Cow::Borrowed("1000")),
+        ]);
+    }
+
+    #[test]
+    fn decimal_separator() {
+        let german = SortOptions::new().decimals(true).decimal_separator(',').thousands_separator('.');
+        let tokens: Vec<_> = Tokens::new("1.000,5 2,5e1", &german.scientific(true)).collect();
+        assert_eq!(tokens, vec![
+            Token::Decimal(Cow::Owned("1000".to_string()), Cow::Borrowed("5")),
+            Token::Text(" "),
+            Token::Decimal(Cow::Owned("25".to_string()), Cow::Owned(String::new())),
+        ]);
+    }
+
+    #[test]
+    fn sizes() {
+        let options = SortOptions::new().sizes(true);
+        let decimal = |integer: &str, fraction: &str| Token::Decimal(Cow::Owned(integer.to_string()), Cow::Owned(fraction.to_string()));
+        let tokens: Vec<_> = Tokens::new("2GB.iso 1.5 KiB 512B 4K 3 Mbps 7 M", &options).collect();
+        assert_eq!(tokens, vec![
+            decimal("2000000000", ""),
+            Token::Text(".iso "),
+            decimal("1536", "0"),
+            Token::Text(" "),
+            decimal("512", ""),
+            Token::Text(" "),
+            decimal("4000", ""),
+            Token::Text(" "),
+            Token::Number(Cow::Borrowed("3")),
+            Token::Text(" Mbps "),
+            Token::Number(Cow::Borrowed("7")),
+            Token::Text(" M"),
+        ]);
+    }
+
+    #[test]
+    fn ip_addresses() {
+        assert_eq!(parse_ipv4("10.0.0.2:80"), Some((8, 0x0A00_0002)));
+        assert_eq!(parse_ipv4("255.255.255.255."), Some((15, u32::MAX)));
+        assert_eq!(parse_ipv4("256.0.0.1"), None);
+        assert_eq!(parse_ipv4("1.2.3"), None);
+        assert_eq!(parse_ipv4("1.2.3.4.5"), None);
+        let options = SortOptions::new().ip_addresses(true).decimals(true);
+        let tokens: Vec<_> = Tokens::new("host-192.168.1.10 v1.2.3.4.5", &options).collect();
+        assert_eq!(tokens, vec![
+            Token::Text("host-"),
+            Token::Number(Cow::Owned("3232235786".to_string())),
+            Token::Text(" v"),
+            Token::Decimal(Cow::Borrowed("1"), Cow::Borrowed("2")),
+            Token::Text("."),
+            Token::Decimal(Cow::Borrowed("3"), Cow::Borrowed("4")),
+            Token::Text("."),
+            Token::Number(Cow::Borrowed("5")),
+        ]);
+    }
+
+    #[test]
+    fn outlines() {
+        let options = SortOptions::new().outlines(true);
+        let levels = |levels: &[&'static str]| Token::Outline(levels.iter().map(|&level| Cow::Borrowed(level)).collect());
+        let tokens: Vec<_> = Tokens::new("1.2.a Scope 1.10. 3.ab", &options).collect();
+        assert_eq!(tokens, vec![
+            levels(&["1", "2", "a"]),
+            Token::Text(" Scope "),
+            levels(&["1", "10"]),
+            Token::Text(". "),
+            Token::Number(Cow::Borrowed("3")),
+            Token::Text(".ab"),
+        ]);
+    }
+}
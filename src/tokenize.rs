@@ -0,0 +1,293 @@
+//! Access to the tokens humane comparison works on.
+//!
+//! Strings are split into runs of digits and runs of everything else, exactly as `humane_cmp`
+//! splits them. `tokens` yields the runs as string slices, `spans` yields their byte ranges,
+//! which are easier to store next to the string or to use for rewriting it. `PushTokenizer`
+//! tokenizes input that arrives in chunks.
+//!
+//! Other segmentation rules can be supplied by implementing `Tokenizer` and comparing with
+//! `humane_cmp_with_tokenizer`.
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::string::String;
+use std::vec::Vec;
+use unicode_segmentation::UnicodeSegmentation;
+use ::{compare_numbers, compare_token_streams_by, numeric_digits, SortingType, TokenIterator};
+
+/// The kind of a token.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum TokenKind {
+    /// A run of digits, compared by value.
+    Number,
+    /// Any other text.
+    Text
+}
+
+impl From<SortingType> for TokenKind {
+    fn from(kind: SortingType) -> Self {
+        match kind {
+            SortingType::Numeric => TokenKind::Number,
+            SortingType::NonNumeric => TokenKind::Text
+        }
+    }
+}
+
+/// Iterator returned by `tokens`.
+pub struct Tokens<'a> {
+    inner: TokenIterator<'a, SortingType>
+}
+
+/// Splits `s` into its tokens.
+///
+/// ```
+/// use humanesort::tokenize::{tokens, TokenKind};
+/// let parts: Vec<_> = tokens("track10.mp3").collect();
+/// assert_eq!(parts, vec![
+///     ("track", TokenKind::Text),
+///     ("10", TokenKind::Number),
+///     (".mp", TokenKind::Text),
+///     ("3", TokenKind::Number),
+/// ]);
+/// ```
+pub fn tokens(s: &str) -> Tokens<'_> {
+    Tokens { inner: TokenIterator::digit_runs(s) }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = (&'a str, TokenKind);
+
+    fn next(&mut self) -> Option<(&'a str, TokenKind)> {
+        self.inner.next().map(|(token, kind)| (token, kind.into()))
+    }
+}
+
+/// Iterator returned by `spans`.
+pub struct Spans<'a> {
+    string: &'a str,
+    inner: Tokens<'a>
+}
+
+/// Splits `s` into its tokens, yielding the byte range of each token in `s`.
+///
+/// ```
+/// use humanesort::tokenize::{spans, TokenKind};
+/// let name = "IMG_0042.jpg";
+/// let (range, _) = spans(name).find(|s| s.1 == TokenKind::Number).unwrap();
+/// assert_eq!(range, 4..8);
+/// let number: u32 = name[range.clone()].parse().unwrap();
+/// let renamed = format!("{}{:05}{}", &name[..range.start], number, &name[range.end..]);
+/// assert_eq!(renamed, "IMG_00042.jpg");
+/// ```
+pub fn spans(s: &str) -> Spans<'_> {
+    Spans { string: s, inner: tokens(s) }
+}
+
+impl<'a> Iterator for Spans<'a> {
+    type Item = (Range<usize>, TokenKind);
+
+    fn next(&mut self) -> Option<(Range<usize>, TokenKind)> {
+        let (token, kind) = self.inner.next()?;
+        let start = token.as_ptr() as usize - self.string.as_ptr() as usize;
+        Some((start..start + token.len(), kind))
+    }
+}
+
+/// Rules for splitting strings into the tokens they are compared by.
+///
+/// A tokenizer only has to find the token at the start of a string, `tokens_with` and
+/// `humane_cmp_with_tokenizer` call it repeatedly on the rest of the string.
+pub trait Tokenizer {
+    /// Returns the length in bytes of the token `s` starts with, and its kind.
+    ///
+    /// `s` is never empty. The length must lie on a character boundary of `s`, a length of 0
+    /// is taken as a token of the first character.
+    fn next_token(&self, s: &str) -> (usize, TokenKind);
+}
+
+/// The tokenizer `humane_cmp` uses, splitting strings into runs of digits and runs of
+/// everything else.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultTokenizer;
+
+impl Tokenizer for DefaultTokenizer {
+    fn next_token(&self, s: &str) -> (usize, TokenKind) {
+        match tokens(s).next() {
+            Some((token, kind)) => (token.len(), kind),
+            None => (0, TokenKind::Text)
+        }
+    }
+}
+
+/// Iterator returned by `tokens_with`.
+pub struct TokensWith<'a, T> where T: Tokenizer + ?Sized + 'a {
+    rest: &'a str,
+    tokenizer: &'a T
+}
+
+/// Splits `s` into tokens with the rules of `tokenizer`.
+///
+/// ```
+/// use humanesort::tokenize::{tokens_with, DefaultTokenizer, TokenKind};
+/// let parts: Vec<_> = tokens_with("v1.2", &DefaultTokenizer).collect();
+/// assert_eq!(parts, vec![("v", TokenKind::Text), ("1", TokenKind::Number), (".", TokenKind::Text), ("2", TokenKind::Number)]);
+/// ```
+pub fn tokens_with<'a, T>(s: &'a str, tokenizer: &'a T) -> TokensWith<'a, T> where T: Tokenizer + ?Sized {
+    TokensWith { rest: s, tokenizer }
+}
+
+impl<'a, T> Iterator for TokensWith<'a, T> where T: Tokenizer + ?Sized {
+    type Item = (&'a str, TokenKind);
+
+    fn next(&mut self) -> Option<(&'a str, TokenKind)> {
+        let first = self.rest.chars().next()?;
+        let (len, kind) = self.tokenizer.next_token(self.rest);
+        let (token, rest) = self.rest.split_at(len.max(first.len_utf8()));
+        self.rest = rest;
+        Some((token, kind))
+    }
+}
+
+/// Compares two strings token by token, splitting them with the rules of `tokenizer`.
+///
+/// Numbers sort before text, numbers compare by the value of their digits and text compares
+/// by code point, as in `humane_cmp`. Here a tokenizer keeps numbers with thousands
+/// separators together:
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use humanesort::HumaneOrder;
+/// use humanesort::tokenize::{humane_cmp_with_tokenizer, DefaultTokenizer, TokenKind, Tokenizer};
+/// struct Separated;
+/// impl Tokenizer for Separated {
+///     fn next_token(&self, s: &str) -> (usize, TokenKind) {
+///         match s.starts_with(|c: char| c.is_ascii_digit()) {
+///             true => (s.find(|c: char| !c.is_ascii_digit() && c != ',').unwrap_or(s.len()), TokenKind::Number),
+///             false => DefaultTokenizer.next_token(s)
+///         }
+///     }
+/// }
+/// assert_eq!("1,500 items".humane_cmp("200 items"), Ordering::Less);
+/// assert_eq!(humane_cmp_with_tokenizer("1,500 items", "200 items", &Separated), Ordering::Greater);
+/// ```
+pub fn humane_cmp_with_tokenizer<T>(ours: &str, theirs: &str, tokenizer: &T) -> Ordering where T: Tokenizer + ?Sized {
+    compare_token_streams_by(tokens_with(ours, tokenizer), tokens_with(theirs, tokenizer), |a, b| match (a.1, b.1) {
+        (TokenKind::Number, TokenKind::Text) => Ordering::Less,
+        (TokenKind::Text, TokenKind::Number) => Ordering::Greater,
+        (TokenKind::Number, TokenKind::Number) => compare_numbers(&numeric_digits(a.0), &numeric_digits(b.0)),
+        (TokenKind::Text, TokenKind::Text) => a.0.cmp(b.0)
+    })
+}
+
+/// A tokenizer for input that arrives in chunks, such as a long line read from a socket.
+///
+/// Tokens that span several chunks are only emitted once they are complete, so the emitted
+/// tokens are the same as those of the concatenated input.
+///
+/// ```
+/// use humanesort::tokenize::{PushTokenizer, TokenKind};
+/// let mut tokenizer = PushTokenizer::new();
+/// let mut numbers = Vec::new();
+/// let mut emit = |token: &str, kind| if kind == TokenKind::Number { numbers.push(token.to_string()) };
+/// for chunk in ["part-1", "23-b", "4"] {
+///     tokenizer.push(chunk, &mut emit);
+/// }
+/// tokenizer.finish(&mut emit);
+/// assert_eq!(numbers, vec!["123", "4"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PushTokenizer {
+    pending: String
+}
+
+impl PushTokenizer {
+    /// Creates a tokenizer that has not seen any input yet.
+    pub fn new() -> Self {
+        PushTokenizer::default()
+    }
+
+    /// Appends `chunk` to the input and passes every token that is complete to `emit`.
+    pub fn push<F>(&mut self, chunk: &str, mut emit: F) where F: FnMut(&str, TokenKind) {
+        self.pending.push_str(chunk);
+        let spans: Vec<_> = spans(&self.pending).collect();
+        // The last token may continue in the next chunk. Its last grapheme may even change
+        // kind, as a digit followed by a combining mark does, and then join the token before.
+        let held = match spans.last() {
+            Some((last, _)) if self.pending[last.clone()].graphemes(true).nth(1).is_none() => 2,
+            _ => 1
+        };
+        let complete = spans.len().saturating_sub(held);
+        for &(ref range, kind) in &spans[..complete] {
+            emit(&self.pending[range.clone()], kind);
+        }
+        if complete > 0 {
+            self.pending.drain(..spans[complete - 1].0.end);
+        }
+    }
+
+    /// Ends the input, passing the remaining tokens to `emit`.
+    pub fn finish<F>(&mut self, mut emit: F) where F: FnMut(&str, TokenKind) {
+        for (token, kind) in tokens(&self.pending) {
+            emit(token, kind);
+        }
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use std::string::ToString;
+    use std::vec::Vec;
+    use super::{humane_cmp_with_tokenizer, spans, tokens, tokens_with, DefaultTokenizer, PushTokenizer, TokenKind, Tokenizer};
+    use ::HumaneOrder;
+
+    #[test]
+    fn spans_cover_string() {
+        let s = "é10 b2";
+        let spans: Vec<_> = spans(s).collect();
+        assert_eq!(spans, vec![
+            (0..2, TokenKind::Text),
+            (2..4, TokenKind::Number),
+            (4..6, TokenKind::Text),
+            (6..7, TokenKind::Number),
+        ]);
+        assert_eq!(super::spans("").count(), 0);
+    }
+
+    #[test]
+    fn push_matches_whole_input() {
+        let input = "a1\u{301}b 12\u{FE0F}\u{20E3}x";
+        let expected: Vec<_> = tokens(input).map(|(t, k)| (t.to_string(), k)).collect();
+        for split in input.char_indices().map(|(i, _)| i) {
+            let mut tokenizer = PushTokenizer::new();
+            let mut actual = Vec::new();
+            tokenizer.push(&input[..split], |t: &str, k| actual.push((t.to_string(), k)));
+            tokenizer.push(&input[split..], |t: &str, k| actual.push((t.to_string(), k)));
+            tokenizer.finish(|t: &str, k| actual.push((t.to_string(), k)));
+            assert_eq!(actual, expected, "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn custom_tokenizer() {
+        // Treats "YYYY-MM-DD" as a single token and everything else as text.
+        struct Dates;
+        impl Tokenizer for Dates {
+            fn next_token(&self, s: &str) -> (usize, TokenKind) {
+                let date = s.len() >= 10 && s.as_bytes()[..10].iter().enumerate()
+                    .all(|(i, b)| if i == 4 || i == 7 { *b == b'-' } else { b.is_ascii_digit() });
+                match date {
+                    true => (10, TokenKind::Number),
+                    false => (s.find(|c: char| c.is_ascii_digit()).unwrap_or(s.len()), TokenKind::Text)
+                }
+            }
+        }
+        let dates: Vec<_> = tokens_with("log 2024-05-01.txt", &Dates).collect();
+        assert_eq!(dates, vec![("log ", TokenKind::Text), ("2024-05-01", TokenKind::Number), (".txt", TokenKind::Text)]);
+        assert_eq!(tokens_with("7", &Dates).collect::<Vec<_>>(), vec![("7", TokenKind::Text)]);
+        assert_eq!(humane_cmp_with_tokenizer("2024-05-01", "2024-5-1", &Dates), Ordering::Less);
+        for (a, b) in [("a10", "a9"), ("x", "x1"), ("", "2"), ("b07", "b7")] {
+            assert_eq!(humane_cmp_with_tokenizer(a, b, &DefaultTokenizer), a.humane_cmp(b));
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Reordering TOML documents into humane key order.
+//!
+//! Comments and formatting stay attached to the keys and tables they belong to, so a document
+//! like a large config file keeps its annotations when "server10" is moved after "server2".
+//!
+//! This module is only available with the `toml_edit` feature.
+use toml_edit::{DocumentMut, InlineTable, Item, Table, Value};
+use ::HumaneOrder;
+
+/// Sorts all keys and tables of `document` in humane order, recursively.
+///
+/// The order of elements within arrays, including arrays of tables, is left unchanged.
+///
+/// ```
+/// # extern crate toml_edit;
+/// # extern crate humanesort;
+/// # fn main() {
+/// use toml_edit::DocumentMut;
+/// use humanesort::toml::sort_document;
+/// let mut document: DocumentMut = "[server10]\nip = 10\n\n# the second one\n[server2]\nip = 2\n".parse().unwrap();
+/// sort_document(&mut document);
+/// let sorted = document.to_string();
+/// assert!(sorted.find("# the second one\n[server2]") < sorted.find("[server10]"));
+/// # }
+/// ```
+pub fn sort_document(document: &mut DocumentMut) {
+    sort_table(document.as_table_mut());
+    let mut position = 0;
+    renumber_table(document.as_table_mut(), &mut position);
+}
+
+/// Sorts the keys of `table` and all nested tables in humane order.
+///
+/// Unlike `sort_document` this does not move table headers, their position in the document is
+/// left to the caller.
+pub fn sort_table(table: &mut Table) {
+    table.sort_values_by(|a, _, b, _| a.get().humane_cmp(b.get()));
+    for (_, item) in table.iter_mut() {
+        sort_item(item);
+    }
+}
+
+fn sort_item(item: &mut Item) {
+    match *item {
+        Item::Table(ref mut table) => sort_table(table),
+        Item::ArrayOfTables(ref mut tables) => {
+            for table in tables.iter_mut() {
+                sort_table(table);
+            }
+        }
+        Item::Value(ref mut value) => sort_value(value),
+        Item::None => ()
+    }
+}
+
+fn sort_value(value: &mut Value) {
+    match *value {
+        Value::InlineTable(ref mut table) => sort_inline_table(table),
+        Value::Array(ref mut array) => {
+            for value in array.iter_mut() {
+                sort_value(value);
+            }
+        }
+        _ => ()
+    }
+}
+
+fn sort_inline_table(table: &mut InlineTable) {
+    // The spacing around keys and values stays at its position, so the separators keep their
+    // layout after the entries move.
+    let decors: Vec<_> = table.iter_mut()
+        .map(|(key, value)| (key.leaf_decor().clone(), key.dotted_decor().clone(), value.decor().clone()))
+        .collect();
+    table.sort_values_by(|a, _, b, _| a.get().humane_cmp(b.get()));
+    for ((mut key, value), (leaf, dotted, decor)) in table.iter_mut().zip(decors) {
+        *key.leaf_decor_mut() = leaf;
+        *key.dotted_decor_mut() = dotted;
+        *value.decor_mut() = decor;
+    }
+    for (_, value) in table.iter_mut() {
+        sort_value(value);
+    }
+}
+
+/// Table headers are emitted by document position, assign positions in the sorted order.
+fn renumber_table(table: &mut Table, position: &mut isize) {
+    for (_, item) in table.iter_mut() {
+        match *item {
+            Item::Table(ref mut child) => {
+                if child.position().is_some() {
+                    *position += 1;
+                    child.set_position(*position);
+                }
+                renumber_table(child, position);
+            }
+            Item::ArrayOfTables(ref mut tables) => {
+                for child in tables.iter_mut() {
+                    if child.position().is_some() {
+                        *position += 1;
+                        child.set_position(*position);
+                    }
+                    renumber_table(child, position);
+                }
+            }
+            _ => ()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use toml_edit::DocumentMut;
+    use super::sort_document;
+
+    #[test]
+    fn nested_tables_and_comments() {
+        let input = "\
+title = \"x\"
+# hosts
+hosts = { web10 = 1, web9 = 2 }
+
+[server10]
+port = 10 # ten
+host = \"a\"
+
+[server2.disk1]
+size = 1
+
+[server2]
+port = 2
+
+[[job]]
+name-2 = 1
+name-11 = 2
+";
+        let mut document: DocumentMut = input.parse().unwrap();
+        sort_document(&mut document);
+        let expected = "\
+# hosts
+hosts = { web9 = 2, web10 = 1 }
+title = \"x\"
+
+[[job]]
+name-2 = 1
+name-11 = 2
+
+[server2]
+port = 2
+
+[server2.disk1]
+size = 1
+
+[server10]
+host = \"a\"
+port = 10 # ten
+";
+        assert_eq!(document.to_string(), expected);
+    }
+}
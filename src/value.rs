@@ -0,0 +1,188 @@
+//! A value type for sorting columns of mixed data.
+//!
+//! Table-like data often mixes numbers, dates, text, and empty cells in one column. `Value`
+//! holds any of these and orders them with a single comparator: empty values first, then
+//! numbers, dates, and finally text in humane order.
+use std::cmp::Ordering;
+use std::fmt;
+use std::string::{String, ToString};
+use ::HumaneOrder;
+
+/// A calendar date.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8
+}
+
+impl Date {
+    /// Parses an ISO 8601 date like "2024-05-01".
+    pub fn parse(s: &str) -> Option<Date> {
+        let mut parts = s.splitn(3, '-');
+        let year = parts.next()?;
+        let month = parts.next()?;
+        let day = parts.next()?;
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return None
+        }
+        let date = Date {
+            year: year.parse().ok()?,
+            month: month.parse().ok()?,
+            day: day.parse().ok()?
+        };
+        match (date.month, date.day) {
+            (1..=12, 1..=31) => Some(date),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A single cell of mixed-type data.
+///
+/// ```
+/// use humanesort::value::Value;
+/// let mut column: Vec<Value> = vec!["item 10", "", "3.5", "item 9", "2024-01-31", "-2"]
+///     .into_iter().map(Value::parse).collect();
+/// column.sort();
+/// let sorted: Vec<String> = column.iter().map(|v| v.to_string()).collect();
+/// assert_eq!(sorted, vec!["", "-2", "3.5", "2024-01-31", "item 9", "item 10"]);
+/// ```
+#[derive(Debug, Clone)]
+pub enum Value {
+    Empty,
+    Number(f64),
+    Date(Date),
+    Text(String)
+}
+
+impl Value {
+    /// Infers the type of `s`: blank strings are `Empty`, then numbers and ISO dates are
+    /// recognized, anything else is kept as `Text`.
+    pub fn parse(s: &str) -> Value {
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Value::Empty
+        }
+        if let Ok(number) = trimmed.parse::<f64>() {
+            if number.is_finite() {
+                return Value::Number(number)
+            }
+        }
+        match Date::parse(trimmed) {
+            Some(date) => Value::Date(date),
+            None => Value::Text(s.to_string())
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match *self {
+            Value::Empty => 0,
+            Value::Number(_) => 1,
+            Value::Date(_) => 2,
+            Value::Text(_) => 3
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Empty => Ok(()),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Date(ref d) => write!(f, "{}", d),
+            Value::Text(ref s) => write!(f, "{}", s)
+        }
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a.total_cmp(b),
+            (Value::Date(a), Value::Date(b)) => a.cmp(b),
+            (Value::Text(a), Value::Text(b)) => a.humane_cmp(b),
+            _ => self.rank().cmp(&other.rank())
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Value {}
+
+impl HumaneOrder for Value {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(n: $t) -> Value {
+                    Value::Number(n as f64)
+                }
+            }
+        )*
+    }
+}
+
+impl_from_number!(f32, f64, i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl From<Date> for Value {
+    fn from(date: Date) -> Value {
+        Value::Date(date)
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Value {
+        Value::Text(s)
+    }
+}
+
+impl<'a> From<&'a str> for Value {
+    fn from(s: &'a str) -> Value {
+        Value::Text(s.to_string())
+    }
+}
+
+impl<T> From<Option<T>> for Value where T: Into<Value> {
+    fn from(value: Option<T>) -> Value {
+        value.map_or(Value::Empty, Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Date, Value};
+
+    #[test]
+    fn conversions_and_order() {
+        assert_eq!(Value::from(3), Value::Number(3.0));
+        assert_eq!(Value::from(None::<i32>), Value::Empty);
+        assert_eq!(Value::parse("2024-02-30"), Value::Date(Date { year: 2024, month: 2, day: 30 }));
+        assert_eq!(Value::parse("2024-13-01"), Value::Text("2024-13-01".into()));
+        assert_eq!(Value::parse("NaN"), Value::Text("NaN".into()));
+        assert!(Value::from("a2") < Value::from("a10"));
+        assert!(Value::from(100) < Value::from("1"));
+    }
+}
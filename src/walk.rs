@@ -0,0 +1,79 @@
+//! Parallel directory traversal yielding entries in humane order.
+//!
+//! Directories are read in parallel by `jwalk`, the entries of each directory are sorted
+//! humanely as soon as it has been read. Iteration yields a depth-first traversal in which
+//! every directory is followed by its humane-sorted contents.
+//!
+//! This module is only available with the `jwalk` feature.
+use std::cmp::Ordering;
+use std::path::Path;
+use jwalk::{ClientState, DirEntry, Result, WalkDir};
+use ::SortOptions;
+use ::path::compare_os_str;
+
+/// Sorts the entries of a single directory humanely by file name, errors are placed last.
+///
+/// This is meant to be called from a custom `process_read_dir` callback, for walkers that need
+/// additional processing besides sorting.
+pub fn sort_children<C>(children: &mut [Result<DirEntry<C>>]) where C: ClientState {
+    sort_children_with(children, &SortOptions::default())
+}
+
+/// Sorts the entries of a single directory using the rules configured in `options`.
+pub fn sort_children_with<C>(children: &mut [Result<DirEntry<C>>], options: &SortOptions) where C: ClientState {
+    children.sort_by(|a, b| match (a, b) {
+        (Ok(a), Ok(b)) => compare_os_str(a.file_name(), b.file_name(), options),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => Ordering::Equal
+    })
+}
+
+/// Creates a parallel walker over `root` that yields the entries of each directory in humane
+/// order.
+///
+/// The returned `WalkDir` can be configured further, e.g. to skip hidden files, as long as
+/// `process_read_dir` is not replaced.
+///
+/// ```no_run
+/// # extern crate humanesort;
+/// # fn main() {
+/// for entry in humanesort::walk::walk_sorted("/var/log") {
+///     println!("{}", entry.unwrap().path().display());
+/// }
+/// # }
+/// ```
+pub fn walk_sorted<P>(root: P) -> WalkDir where P: AsRef<Path> {
+    walk_sorted_with(root, SortOptions::default())
+}
+
+/// Creates a parallel walker like `walk_sorted`, comparing names with the rules configured in
+/// `options`.
+pub fn walk_sorted_with<P>(root: P, options: SortOptions) -> WalkDir where P: AsRef<Path> {
+    WalkDir::new(root)
+        .sort(false)
+        .process_read_dir(move |_, _, _, children| sort_children_with(children, &options))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use super::walk_sorted;
+
+    #[test]
+    fn directories_in_humane_order() {
+        let root = env::temp_dir().join(format!("humanesort-walk-{}", ::std::process::id()));
+        for dir in &["d10", "d2/e10", "d2/e9", "d1"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("d2/f-3"), "").unwrap();
+        let found: Vec<String> = walk_sorted(&root)
+            .into_iter()
+            .skip(1)
+            .map(|e| e.unwrap().path().strip_prefix(&root).unwrap().to_string_lossy().into_owned())
+            .collect();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(found, vec!["d1", "d2", "d2/e9", "d2/e10", "d2/f-3", "d10"]);
+    }
+}
@@ -0,0 +1,42 @@
+//! JavaScript bindings through `wasm-bindgen`, so web front ends order lists exactly like a
+//! Rust back end does.
+//!
+//! Built for `wasm32-unknown-unknown` and processed with `wasm-bindgen`, the module exports
+//! `compare(a, b)`, usable as the callback of `Array.prototype.sort`, and `sort(array)`:
+//!
+//! ```js
+//! import { compare, sort } from "humanesort";
+//! names.sort(compare);
+//! const sorted = sort(["file10", "file9"]); // ["file9", "file10"]
+//! ```
+use std::cmp::Ordering;
+use std::string::String;
+use std::vec::Vec;
+use wasm_bindgen::prelude::wasm_bindgen;
+use ::{HumaneOrder, HumaneSortable};
+
+/// Compares two strings like `humane_cmp`, returning -1, 0 or 1 like a JavaScript comparator.
+///
+/// ```
+/// assert_eq!(humanesort::wasm::compare("file9", "file10"), -1);
+/// ```
+#[wasm_bindgen]
+pub fn compare(a: &str, b: &str) -> i32 {
+    match a.humane_cmp(b) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1
+    }
+}
+
+/// Returns the strings of `array` in humane order, leaving the array itself unchanged.
+///
+/// ```
+/// assert_eq!(humanesort::wasm::sort(vec!["file10".into(), "file9".into()]), ["file9", "file10"]);
+/// ```
+#[wasm_bindgen]
+pub fn sort(array: Vec<String>) -> Vec<String> {
+    let mut array = array;
+    array.humane_sort();
+    array
+}
@@ -0,0 +1,581 @@
+//! Wrapper types that use humane ordering for their `Ord` implementation.
+use std::borrow::{Borrow, ToOwned};
+use std::boxed::Box;
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt;
+#[cfg(feature = "std")]
+use std::fmt::Write;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::string::{String, ToString};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ::{numeric_digits, HumaneOrder, SortOptions, SortingType, TokenIterator};
+
+/// A borrowed string slice ordered humanely, like `Path` is a borrowed `OsStr` with path
+/// semantics.
+///
+/// `HumaneStr` implements `Ord`, `Eq` and `Hash` with humane semantics, so strings that only
+/// differ in leading zeros of numbers ("file007", "file7") are equal. Owned keys are stored as
+/// `Box<HumaneStr>` or `HumaneString`, lookups borrow plain strings with `HumaneStr::new`:
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use humanesort::HumaneStr;
+/// let mut map: BTreeMap<Box<HumaneStr>, u32> = BTreeMap::new();
+/// map.insert("file10".into(), 10);
+/// map.insert("file9".into(), 9);
+/// assert_eq!(map.keys().map(|k| k.as_str()).collect::<Vec<_>>(), vec!["file9", "file10"]);
+/// assert_eq!(map.get(HumaneStr::new("file09")), Some(&9));
+/// ```
+#[repr(transparent)]
+pub struct HumaneStr(str);
+
+impl HumaneStr {
+    /// Wraps a string slice, this is a free conversion.
+    pub fn new<S>(s: &S) -> &HumaneStr where S: AsRef<str> + ?Sized {
+        let s: &str = s.as_ref();
+        // HumaneStr is a transparent wrapper around str, so the pointer cast is sound.
+        unsafe { &*(s as *const str as *const HumaneStr) }
+    }
+
+    /// Returns the wrapped string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Converts a boxed `HumaneStr` into a boxed `str` without copying.
+    pub fn into_boxed_str(self: Box<HumaneStr>) -> Box<str> {
+        let raw = Box::into_raw(self) as *mut str;
+        unsafe { Box::from_raw(raw) }
+    }
+}
+
+impl Deref for HumaneStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for HumaneStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<HumaneStr> for HumaneStr {
+    fn as_ref(&self) -> &HumaneStr {
+        self
+    }
+}
+
+impl<'a> From<&'a str> for &'a HumaneStr {
+    fn from(s: &'a str) -> &'a HumaneStr {
+        HumaneStr::new(s)
+    }
+}
+
+impl From<Box<str>> for Box<HumaneStr> {
+    fn from(s: Box<str>) -> Box<HumaneStr> {
+        let raw = Box::into_raw(s) as *mut HumaneStr;
+        unsafe { Box::from_raw(raw) }
+    }
+}
+
+impl<'a> From<&'a str> for Box<HumaneStr> {
+    fn from(s: &'a str) -> Box<HumaneStr> {
+        Box::<str>::from(s).into()
+    }
+}
+
+impl From<String> for Box<HumaneStr> {
+    fn from(s: String) -> Box<HumaneStr> {
+        s.into_boxed_str().into()
+    }
+}
+
+impl<'a> From<&'a HumaneStr> for Box<HumaneStr> {
+    fn from(s: &'a HumaneStr) -> Box<HumaneStr> {
+        s.as_str().into()
+    }
+}
+
+impl Clone for Box<HumaneStr> {
+    fn clone(&self) -> Self {
+        self.as_str().into()
+    }
+}
+
+impl Default for &HumaneStr {
+    fn default() -> Self {
+        HumaneStr::new("")
+    }
+}
+
+impl fmt::Debug for HumaneStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for HumaneStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl HumaneOrder for HumaneStr {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        self.0.humane_cmp(&other.0)
+    }
+}
+
+impl Ord for HumaneStr {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.humane_cmp(&other.0)
+    }
+}
+
+impl PartialOrd for HumaneStr {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HumaneStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HumaneStr {}
+
+impl Hash for HumaneStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_humane(&self.0, state)
+    }
+}
+
+impl ToOwned for HumaneStr {
+    type Owned = HumaneString;
+
+    fn to_owned(&self) -> HumaneString {
+        HumaneString(self.0.to_owned())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for HumaneStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de: 'a, 'a> Deserialize<'de> for &'a HumaneStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        <&str>::deserialize(deserializer).map(HumaneStr::new)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Box<HumaneStr> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        String::deserialize(deserializer).map(Box::from)
+    }
+}
+
+/// An owned string ordered humanely, the owned counterpart of `HumaneStr`.
+///
+/// Like `HumaneStr` it implements `Ord`, `Eq` and `Hash` with humane semantics, so it can be
+/// used as the key of a `BTreeMap`, `HashMap` or `BinaryHeap`. It dereferences to `HumaneStr`
+/// and through it to `str`, and maps keyed by it can be queried with a borrowed `HumaneStr`:
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use humanesort::{HumaneStr, HumaneString};
+/// let mut set: BTreeSet<HumaneString> = BTreeSet::new();
+/// set.insert("v1.10".into());
+/// set.insert(HumaneString::from(String::from("v1.9")));
+/// assert_eq!(set.iter().map(|s| s.as_str()).collect::<Vec<_>>(), vec!["v1.9", "v1.10"]);
+/// assert!(set.contains(HumaneStr::new("v1.09")));
+/// assert_eq!(set.first().map(|s| s.len()), Some(4));
+/// ```
+#[derive(Clone, Default)]
+pub struct HumaneString(String);
+
+impl HumaneString {
+    /// Creates an empty string.
+    pub fn new() -> HumaneString {
+        HumaneString(String::new())
+    }
+
+    /// Returns the wrapped string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the wrapped string slice as a `HumaneStr`.
+    pub fn as_humane_str(&self) -> &HumaneStr {
+        HumaneStr::new(&self.0)
+    }
+
+    /// Unwraps the `String`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for HumaneString {
+    type Target = HumaneStr;
+
+    fn deref(&self) -> &HumaneStr {
+        self.as_humane_str()
+    }
+}
+
+impl Borrow<HumaneStr> for HumaneString {
+    fn borrow(&self) -> &HumaneStr {
+        self.as_humane_str()
+    }
+}
+
+impl AsRef<str> for HumaneString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<HumaneStr> for HumaneString {
+    fn as_ref(&self) -> &HumaneStr {
+        self.as_humane_str()
+    }
+}
+
+impl From<String> for HumaneString {
+    fn from(s: String) -> HumaneString {
+        HumaneString(s)
+    }
+}
+
+impl<'a> From<&'a str> for HumaneString {
+    fn from(s: &'a str) -> HumaneString {
+        HumaneString(s.to_owned())
+    }
+}
+
+impl<'a> From<&'a HumaneStr> for HumaneString {
+    fn from(s: &'a HumaneStr) -> HumaneString {
+        s.to_owned()
+    }
+}
+
+impl From<HumaneString> for String {
+    fn from(s: HumaneString) -> String {
+        s.0
+    }
+}
+
+impl fmt::Debug for HumaneString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for HumaneString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl HumaneOrder for HumaneString {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        self.0.humane_cmp(&other.0)
+    }
+}
+
+impl Ord for HumaneString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.humane_cmp(&other.0)
+    }
+}
+
+impl PartialOrd for HumaneString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HumaneString {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HumaneString {}
+
+impl Hash for HumaneString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_humane(&self.0, state)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for HumaneString {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for HumaneString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        String::deserialize(deserializer).map(HumaneString)
+    }
+}
+
+/// Compares any `Display` type by its rendering, so identifiers and newtypes from other crates
+/// can be sorted humanely without mapping them to strings first.
+///
+/// Both values are rendered into buffers that are reused across comparisons on the same
+/// thread. Sorting with `humane_sort_by_cached_key(|x| x.to_string())` instead renders every
+/// value only once, at the cost of keeping all renderings in memory. Without the `std` feature
+/// there are no thread-local buffers and every comparison renders into new strings.
+///
+/// ```
+/// use std::net::Ipv4Addr;
+/// use humanesort::{ByDisplay, HumaneSortable};
+/// let mut addresses = [ByDisplay(Ipv4Addr::new(10, 0, 0, 10)), ByDisplay(Ipv4Addr::new(10, 0, 0, 9))];
+/// addresses.humane_sort();
+/// assert_eq!(addresses[0].0, Ipv4Addr::new(10, 0, 0, 9));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByDisplay<T>(pub T);
+
+#[cfg(feature = "std")]
+thread_local! {
+    static RENDER_BUFFERS: RefCell<(String, String)> = const { RefCell::new((String::new(), String::new())) };
+}
+
+#[cfg(feature = "std")]
+fn compare_rendered<T>(ours: &T, theirs: &T, options: &SortOptions) -> Ordering where T: fmt::Display {
+    RENDER_BUFFERS.with(|buffers| match buffers.try_borrow_mut() {
+        Ok(mut buffers) => {
+            let (ref mut a, ref mut b) = *buffers;
+            a.clear();
+            b.clear();
+            // Writing to a String only fails if the Display implementation fails, which leaves
+            // the rendering incomplete but comparable.
+            let _ = write!(a, "{}", ours);
+            let _ = write!(b, "{}", theirs);
+            a.as_str().humane_cmp_with(b.as_str(), options)
+        }
+        // A Display implementation that compares other values itself.
+        Err(_) => ours.to_string().humane_cmp_with(&theirs.to_string(), options)
+    })
+}
+
+#[cfg(not(feature = "std"))]
+fn compare_rendered<T>(ours: &T, theirs: &T, options: &SortOptions) -> Ordering where T: fmt::Display {
+    ours.to_string().humane_cmp_with(&theirs.to_string(), options)
+}
+
+impl<T> HumaneOrder for ByDisplay<T> where T: fmt::Display {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        compare_rendered(&self.0, &other.0, &SortOptions::default())
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        compare_rendered(&self.0, &other.0, options)
+    }
+}
+
+impl<T> Ord for ByDisplay<T> where T: fmt::Display {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.humane_cmp(other)
+    }
+}
+
+impl<T> PartialOrd for ByDisplay<T> where T: fmt::Display {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> PartialEq for ByDisplay<T> where T: fmt::Display {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for ByDisplay<T> where T: fmt::Display {}
+
+#[cfg(feature = "serde")]
+impl<T> Serialize for ByDisplay<T> where T: Serialize {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> Deserialize<'de> for ByDisplay<T> where T: Deserialize<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        T::deserialize(deserializer).map(ByDisplay)
+    }
+}
+
+/// Reverses the humane order of the wrapped value, like `std::cmp::Reverse` does for `Ord`.
+///
+/// ```
+/// use humanesort::HumaneReverse;
+/// let mut names = vec!["img-9", "img-10", "img-1"];
+/// names.sort_by_key(|&name| HumaneReverse(name));
+/// assert_eq!(names, vec!["img-10", "img-9", "img-1"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HumaneReverse<T>(pub T);
+
+impl<T> HumaneOrder for HumaneReverse<T> where T: HumaneOrder {
+    fn humane_cmp(&self, other: &Self) -> Ordering {
+        other.0.humane_cmp(&self.0)
+    }
+
+    fn humane_cmp_with(&self, other: &Self, options: &SortOptions) -> Ordering {
+        other.0.humane_cmp_with(&self.0, options)
+    }
+}
+
+impl<T> Ord for HumaneReverse<T> where T: HumaneOrder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.humane_cmp(other)
+    }
+}
+
+impl<T> PartialOrd for HumaneReverse<T> where T: HumaneOrder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> PartialEq for HumaneReverse<T> where T: HumaneOrder {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for HumaneReverse<T> where T: HumaneOrder {}
+
+/// Hashes `s` so that humane-equal strings produce the same hash.
+pub(crate) fn hash_humane<H: Hasher>(s: &str, state: &mut H) {
+    for (token, kind) in TokenIterator::digit_runs(s) {
+        match kind {
+            SortingType::Numeric => {
+                state.write_u8(0);
+                numeric_digits(token).trim_start_matches('0').hash(state);
+            }
+            SortingType::NonNumeric => {
+                state.write_u8(1);
+                token.hash(state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+    use std::boxed::Box;
+    #[cfg(feature = "std")]
+    use std::collections::hash_map::DefaultHasher;
+    #[cfg(feature = "std")]
+    use std::collections::HashSet;
+    #[cfg(feature = "std")]
+    use std::hash::{Hash, Hasher};
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+    use super::{ByDisplay, HumaneReverse, HumaneStr, HumaneString};
+
+    #[cfg(feature = "std")]
+    fn hash(s: &HumaneStr) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        s.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equality_agrees_with_order() {
+        assert_eq!(HumaneStr::new("img007"), HumaneStr::new("img7"));
+        assert!(HumaneStr::new("img-2") < HumaneStr::new("img-10"));
+        let boxed: Box<HumaneStr> = String::from("x").into();
+        assert_eq!(&*boxed.clone().into_boxed_str(), "x");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_agrees_with_equality() {
+        assert_eq!(hash(HumaneStr::new("img007")), hash(HumaneStr::new("img7")));
+        let mut set: HashSet<Box<HumaneStr>> = HashSet::new();
+        set.insert("a01b".into());
+        assert!(set.contains(HumaneStr::new("a1b")));
+        assert_eq!(hash(&HumaneString::from("img007")), hash(HumaneStr::new("img7")));
+    }
+
+    #[test]
+    fn owned_matches_borrowed() {
+        use std::collections::BinaryHeap;
+        let owned = HumaneString::from("img007");
+        assert_eq!(owned, HumaneString::from("img7"));
+        assert_eq!(HumaneStr::new("x2").to_owned().into_string(), "x2");
+        let mut heap: BinaryHeap<HumaneString> = vec!["p9", "p10", "p2"].into_iter().map(HumaneString::from).collect();
+        assert_eq!(heap.pop().map(String::from), Some("p10".to_string()));
+    }
+
+    #[test]
+    fn reverse_order() {
+        use std::collections::BinaryHeap;
+        use ::{HumaneOrder, SortOptions};
+        let mut heap: BinaryHeap<_> = ["p10", "p9", "p2"].iter().map(|&p| HumaneReverse(p)).collect();
+        assert_eq!(heap.pop().map(|p| p.0), Some("p2"));
+        assert_eq!(HumaneReverse("a07"), HumaneReverse("a7"));
+        let options = SortOptions::new().decimals(true);
+        assert!(HumaneReverse("1.5").humane_cmp_with(&HumaneReverse("1.25"), &options).is_lt());
+    }
+
+    #[test]
+    fn display_order() {
+        use std::fmt;
+        struct Nested(Vec<ByDisplay<u32>>);
+        impl fmt::Display for Nested {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let mut sorted = self.0.clone();
+                sorted.sort();
+                write!(f, "n{}", sorted[0].0)
+            }
+        }
+        assert!(ByDisplay(9) < ByDisplay(10));
+        assert_eq!(ByDisplay("x07"), ByDisplay("x7"));
+        let mut nested = [ByDisplay(Nested(vec![ByDisplay(12), ByDisplay(3)])), ByDisplay(Nested(vec![ByDisplay(20)]))];
+        nested.sort();
+        assert_eq!(nested[0].0.to_string(), "n3");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_transparently() {
+        use std::collections::BTreeMap;
+        use serde::Deserialize;
+        use serde::de::value::{BorrowedStrDeserializer, Error, MapDeserializer, StrDeserializer};
+        let entries = vec![("node10", 2), ("node9", 1), ("node010", 3)];
+        let map = BTreeMap::<HumaneString, u32>::deserialize(MapDeserializer::<_, Error>::new(entries.into_iter())).unwrap();
+        assert_eq!(map.iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(), vec![("node9", 1), ("node10", 3)]);
+        let borrowed = <&HumaneStr>::deserialize(BorrowedStrDeserializer::<Error>::new("a7")).unwrap();
+        assert_eq!(borrowed, HumaneStr::new("a07"));
+        let boxed = Box::<HumaneStr>::deserialize(StrDeserializer::<Error>::new("a7")).unwrap();
+        assert_eq!(boxed.as_str(), "a7");
+        assert_eq!(ByDisplay::<u8>::deserialize(serde::de::IntoDeserializer::<Error>::into_deserializer(9u8)).unwrap().0, 9);
+    }
+}
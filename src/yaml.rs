@@ -0,0 +1,108 @@
+//! Re-emitting YAML with mapping keys in humane order.
+//!
+//! The document is parsed and serialized again, so comments and the original formatting are
+//! not preserved.
+//!
+//! This module is only available with the `yaml` feature.
+use std::cmp::Ordering;
+use std::mem;
+use serde::Deserialize;
+use serde_yaml::{self, Deserializer, Value};
+use ::HumaneOrder;
+
+/// Text used for ordering a mapping key, scalar keys are compared by their textual form.
+fn key_text(key: &Value) -> String {
+    match *key {
+        Value::String(ref s) => s.clone(),
+        Value::Number(ref n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => String::new(),
+        _ => serde_yaml::to_string(key).unwrap_or_default()
+    }
+}
+
+fn compare_keys(a: &Value, b: &Value) -> Ordering {
+    key_text(a).humane_cmp(&key_text(b))
+}
+
+/// Sorts the keys of all mappings in `value` in humane order, recursively.
+///
+/// ```
+/// # extern crate serde_yaml;
+/// # extern crate humanesort;
+/// # fn main() {
+/// use humanesort::yaml::sort_keys;
+/// let mut value: serde_yaml::Value = serde_yaml::from_str("node10: b\nnode9: a\n").unwrap();
+/// sort_keys(&mut value);
+/// assert_eq!(serde_yaml::to_string(&value).unwrap(), "node9: a\nnode10: b\n");
+/// # }
+/// ```
+pub fn sort_keys(value: &mut Value) {
+    match *value {
+        Value::Mapping(ref mut mapping) => {
+            let mut entries: Vec<(Value, Value)> = mem::take(mapping).into_iter().collect();
+            entries.sort_by(|a, b| compare_keys(&a.0, &b.0));
+            for entry in &mut entries {
+                sort_keys(&mut entry.1);
+            }
+            *mapping = entries.into_iter().collect();
+        }
+        Value::Sequence(ref mut sequence) => {
+            for item in sequence {
+                sort_keys(item);
+            }
+        }
+        Value::Tagged(ref mut tagged) => sort_keys(&mut tagged.value),
+        _ => ()
+    }
+}
+
+/// Parses `input`, which may contain several `---` separated documents, and re-emits it with
+/// all mapping keys in humane order.
+pub fn normalize(input: &str) -> Result<String, serde_yaml::Error> {
+    let mut documents = Vec::new();
+    for document in Deserializer::from_str(input) {
+        let mut value = Value::deserialize(document)?;
+        sort_keys(&mut value);
+        documents.push(serde_yaml::to_string(&value)?);
+    }
+    Ok(documents.join("---\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize;
+
+    #[test]
+    fn normalize_multiple_documents() {
+        let input = "\
+kind: Pod
+metadata:
+  labels:
+    tier-10: x
+    tier-2: y
+spec:
+  containers:
+  - name: c
+    image: i
+---
+3: three
+20: twenty
+";
+        let expected = "\
+kind: Pod
+metadata:
+  labels:
+    tier-2: y
+    tier-10: x
+spec:
+  containers:
+  - image: i
+    name: c
+---
+3: three
+20: twenty
+";
+        assert_eq!(normalize(input).unwrap(), expected);
+    }
+}